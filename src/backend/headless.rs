@@ -0,0 +1,356 @@
+use crate::{
+    constants::{DEFAULT_HEIGHT_U32, DEFAULT_WIDTH_U32},
+    error::ResultX,
+    events::{keyboard::K, mouse::MouseButton},
+    internal::{keys::Keys, rate::Fps},
+    renderer::two_d::ResizePolicy,
+};
+
+use super::{Backend, blit_scaled};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptedEvent {
+    KeyDown(K),
+    KeyUp(K),
+    MouseMove(f32, f32),
+    ButtonDown(MouseButton),
+    ButtonUp(MouseButton),
+    Resize(u32, u32),
+    Close,
+}
+
+/// Input that gets fed to a [`Headless`] backend, keyed by frame index.
+#[derive(Debug, Default, Clone)]
+pub struct EventScript {
+    events: Vec<(u64, ScriptedEvent)>,
+}
+
+impl EventScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn at(mut self, frame: u64, event: ScriptedEvent) -> Self {
+        self.push(frame, event);
+        self
+    }
+
+    pub fn push(&mut self, frame: u64, event: ScriptedEvent) {
+        self.events.push((frame, event));
+    }
+
+    fn take_frame(&mut self, frame: u64) -> Vec<ScriptedEvent> {
+        let mut out = Vec::new();
+        self.events.retain(|(f, ev)| {
+            if *f == frame {
+                out.push(*ev);
+                false
+            } else {
+                true
+            }
+        });
+        out
+    }
+}
+
+/// In-memory backend, nothing is ever shown on screen.
+/// Runs until `max_frames` is reached or a [`ScriptedEvent::Close`] shows up.
+pub struct Headless {
+    width: u32,
+    height: u32,
+    mouse_x: f32,
+    mouse_y: f32,
+    buttons: [u8; 3],
+    key_state: Keys,
+    fps: Fps,
+    frame: u64,
+    max_frames: Option<u64>,
+    should_close: bool,
+    resized: Option<(u32, u32)>,
+    script: EventScript,
+    buffer: Vec<u32>,
+}
+
+impl Headless {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width: width as u32,
+            height: height as u32,
+            mouse_x: 0.0,
+            mouse_y: 0.0,
+            buttons: [0, 0, 0],
+            key_state: Keys::new(),
+            fps: Fps::with_fps(0),
+            frame: 0,
+            max_frames: None,
+            should_close: false,
+            resized: None,
+            script: EventScript::new(),
+            buffer: vec![0; width * height],
+        }
+    }
+
+    /// Stops after `n` calls to `State::user_update`.
+    pub fn with_frames(mut self, n: u64) -> Self {
+        self.max_frames = Some(n);
+        self.should_close = n == 0;
+        self
+    }
+
+    /// Events scheduled for frame 0 are already visible in the first update.
+    pub fn with_script(mut self, script: EventScript) -> Self {
+        self.script = script;
+        self.apply_frame();
+        self
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The last frame as it would have been shown in a window of the current size.
+    pub fn frame_buffer(&self) -> &[u32] {
+        &self.buffer
+    }
+
+    /// Same order as the x11 window: key state moves on a frame first, then the new
+    /// events come in, so releases are seen too.
+    fn apply_frame(&mut self) {
+        self.key_state.update();
+        for ev in self.script.take_frame(self.frame) {
+            match ev {
+                ScriptedEvent::KeyDown(k) => self.key_state.set(k),
+                ScriptedEvent::KeyUp(k) => self.key_state.clear(k),
+                ScriptedEvent::MouseMove(x, y) => {
+                    self.mouse_x = x;
+                    self.mouse_y = y;
+                }
+                ScriptedEvent::ButtonDown(b) => self.buttons[b as usize] = 1,
+                ScriptedEvent::ButtonUp(b) => self.buttons[b as usize] = 0,
+                ScriptedEvent::Resize(w, h) => {
+                    if (w, h) != (self.width, self.height) {
+                        self.width = w;
+                        self.height = h;
+                        self.resized = Some((w, h));
+                    }
+                }
+                ScriptedEvent::Close => self.should_close = true,
+            }
+        }
+    }
+}
+
+impl Default for Headless {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH_U32 as usize, DEFAULT_HEIGHT_U32 as usize)
+    }
+}
+
+impl Backend for Headless {
+    fn should_close(&self) -> bool {
+        self.should_close
+    }
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn update_with_buffer_stride(
+        &mut self,
+        buffer: &[u32],
+        buf_width: usize,
+        buf_height: usize,
+        buf_stride: usize,
+        policy: ResizePolicy,
+    ) -> ResultX<()> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        self.buffer.resize(width * height, 0);
+        blit_scaled(
+            buffer,
+            (buf_width, buf_height),
+            buf_stride,
+            &mut self.buffer,
+            (width, height),
+            policy,
+        );
+        self.fps.update();
+        self.frame += 1;
+        if self.max_frames.is_some_and(|max| self.frame >= max) {
+            self.should_close = true;
+        }
+        self.apply_frame();
+        Ok(())
+    }
+
+    fn take_resize(&mut self) -> Option<(u32, u32)> {
+        self.resized.take()
+    }
+
+    fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn get_screen_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn get_mouse_position(&self) -> Option<(f32, f32)> {
+        Some((self.mouse_x, self.mouse_y))
+    }
+
+    fn key_pressed(&self, k: K) -> bool {
+        self.key_state.is_pressed(k)
+    }
+
+    fn key_down(&self, k: K) -> bool {
+        self.key_state.is_down(k)
+    }
+
+    fn key_released(&self, k: K) -> bool {
+        self.key_state.is_released(k)
+    }
+
+    fn button_is_down(&self, b: usize) -> bool {
+        self.buttons[b] > 0
+    }
+
+    fn get_fps(&self) -> usize {
+        self.fps.get_target_fps()
+    }
+
+    fn set_target_fps(&mut self, fps: usize) {
+        self.fps.set_target_fps(fps);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        backend::{
+            Backend,
+            headless::{EventScript, Headless, ScriptedEvent},
+        },
+        context::time::TimeInfo,
+        engine::{camera_2d::Camera2dRef, two_d::Engine},
+        events::{input::Events, keyboard::K},
+        renderer::two_d::{Renderer, ResizePolicy},
+        state::two_d::State,
+    };
+
+    struct Counter {
+        frames: u64,
+        space_down: Vec<u64>,
+        resized: Vec<(usize, usize)>,
+    }
+
+    impl State for Counter {
+        fn user_init(_renderer: &mut Renderer, _camera: Camera2dRef) -> Self {
+            Self {
+                frames: 0,
+                space_down: Vec::new(),
+                resized: Vec::new(),
+            }
+        }
+
+        fn user_update(
+            &mut self,
+            renderer: &mut Renderer,
+            events: Events,
+            _camera: Camera2dRef,
+            _time_info: &TimeInfo,
+        ) -> bool {
+            if events.key_down(K::Space) {
+                self.space_down.push(self.frames);
+            }
+            renderer.clear_background(self.frames as u32);
+            self.frames += 1;
+            events.key_down(K::Escape)
+        }
+
+        fn user_resize(&mut self, _renderer: &mut Renderer, width: usize, height: usize) {
+            self.resized.push((width, height));
+        }
+    }
+
+    #[test]
+    fn runs_n_frames() {
+        let backend = Headless::new(32, 16).with_frames(5);
+        let mut engine = Engine::<Counter>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert_eq!(engine.state().frames, 5);
+        assert!(
+            engine
+                .renderer()
+                .buffer()
+                .as_slice()
+                .iter()
+                .all(|p| *p == 4)
+        );
+    }
+
+    #[test]
+    fn scripted_input() {
+        let script = EventScript::new()
+            .at(2, ScriptedEvent::KeyDown(K::Space))
+            .at(4, ScriptedEvent::KeyUp(K::Space))
+            .at(6, ScriptedEvent::KeyDown(K::Escape));
+        let backend = Headless::new(32, 16).with_frames(100).with_script(script);
+        let mut engine = Engine::<Counter>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert_eq!(engine.state().space_down, vec![2, 3]);
+        assert_eq!(engine.state().frames, 7);
+    }
+
+    #[test]
+    fn scripted_close() {
+        let script = EventScript::new().at(0, ScriptedEvent::Close);
+        let backend = Headless::new(32, 16).with_script(script);
+        let mut engine = Engine::<Counter>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert_eq!(engine.state().frames, 0);
+    }
+
+    #[test]
+    fn resize_follow() {
+        let script = EventScript::new().at(2, ScriptedEvent::Resize(40, 20));
+        let backend = Headless::new(32, 16).with_frames(4).with_script(script);
+        let mut engine = Engine::<Counter>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert_eq!(engine.state().resized, vec![(40, 20)]);
+        assert_eq!(engine.renderer().buffer().width(), 40);
+        assert_eq!(engine.renderer().buffer().height(), 20);
+    }
+
+    #[test]
+    fn resize_to_zero() {
+        let script = EventScript::new()
+            .at(1, ScriptedEvent::Resize(0, 0))
+            .at(3, ScriptedEvent::Resize(40, 0))
+            .at(4, ScriptedEvent::Resize(40, 20));
+        let backend = Headless::new(32, 16).with_frames(6).with_script(script);
+        let mut engine = Engine::<Counter>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert_eq!(engine.state().frames, 6);
+        assert_eq!(engine.state().resized, vec![(40, 20)]);
+        assert_eq!(engine.renderer().buffer().width(), 40);
+
+        let mut renderer = Renderer::new(32, 16);
+        renderer.resize(0, 16);
+        assert_eq!(renderer.buffer().width(), 32);
+    }
+
+    #[test]
+    fn letterbox() {
+        let mut backend = Headless::new(8, 4);
+        let src = [7u32; 4 * 4];
+        backend
+            .update_with_buffer_stride(&src, 4, 4, 4, ResizePolicy::Letterbox)
+            .unwrap();
+        for y in 0..4 {
+            let row = &backend.frame_buffer()[y * 8..(y + 1) * 8];
+            assert_eq!(row, &[0, 0, 7, 7, 7, 7, 0, 0]);
+        }
+        backend
+            .update_with_buffer_stride(&src, 4, 4, 4, ResizePolicy::Stretch)
+            .unwrap();
+        assert!(backend.frame_buffer().iter().all(|p| *p == 7));
+    }
+}
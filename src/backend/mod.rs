@@ -0,0 +1,76 @@
+pub mod headless;
+
+use crate::{error::ResultX, events::keyboard::K, renderer::two_d::ResizePolicy};
+
+/// Whatever the engine presents frames to and reads input from.
+/// The x11 window is one implementation, [`headless::Headless`] is another.
+pub trait Backend {
+    fn should_close(&self) -> bool;
+    fn set_title(&mut self, title: &str);
+    fn update_with_buffer_stride(
+        &mut self,
+        buffer: &[u32],
+        buf_width: usize,
+        buf_height: usize,
+        buf_stride: usize,
+        policy: ResizePolicy,
+    ) -> ResultX<()>;
+    /// New window size, if it changed since the last call.
+    fn take_resize(&mut self) -> Option<(u32, u32)>;
+    fn get_size(&self) -> (u32, u32);
+    fn get_screen_size(&self) -> (u32, u32);
+    fn get_mouse_position(&self) -> Option<(f32, f32)>;
+    fn key_pressed(&self, k: K) -> bool;
+    fn key_down(&self, k: K) -> bool;
+    fn key_released(&self, k: K) -> bool;
+    fn button_is_down(&self, b: usize) -> bool;
+    fn get_fps(&self) -> usize;
+    fn set_target_fps(&mut self, fps: usize);
+}
+
+/// Copies `src` into the window sized `dst`, scaling according to `policy`.
+/// Nearest neighbour only, pixels not covered by `src` are cleared.
+pub(crate) fn blit_scaled(
+    src: &[u32],
+    (src_width, src_height): (usize, usize),
+    src_stride: usize,
+    dst: &mut [u32],
+    (dst_width, dst_height): (usize, usize),
+    policy: ResizePolicy,
+) {
+    if src_width == 0 || src_height == 0 || dst_width == 0 || dst_height == 0 {
+        return;
+    }
+    let (out_w, out_h) = match policy {
+        ResizePolicy::Follow => (src_width.min(dst_width), src_height.min(dst_height)),
+        ResizePolicy::Stretch => (dst_width, dst_height),
+        ResizePolicy::Letterbox => {
+            let scale =
+                (dst_width as f32 / src_width as f32).min(dst_height as f32 / src_height as f32);
+            (
+                ((src_width as f32 * scale) as usize).clamp(1, dst_width),
+                ((src_height as f32 * scale) as usize).clamp(1, dst_height),
+            )
+        }
+    };
+    let (off_x, off_y) = match policy {
+        ResizePolicy::Letterbox => ((dst_width - out_w) / 2, (dst_height - out_h) / 2),
+        _ => (0, 0),
+    };
+    let scaled = policy != ResizePolicy::Follow;
+
+    dst.fill(0);
+    for y in 0..out_h {
+        let sy = if scaled { y * src_height / out_h } else { y };
+        let src_row = &src[sy * src_stride..sy * src_stride + src_width];
+        let dst_row =
+            &mut dst[(y + off_y) * dst_width + off_x..(y + off_y) * dst_width + off_x + out_w];
+        if scaled {
+            for (x, p) in dst_row.iter_mut().enumerate() {
+                *p = src_row[x * src_width / out_w];
+            }
+        } else {
+            dst_row.copy_from_slice(&src_row[..out_w]);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::alloc::Layout;
 
-#[derive(Clone)]
+use crate::colors::modify::{BlendMode, blend, scale_alpha};
+
 pub struct Buffer<T: Copy + Default> {
     data: *mut T,
     width: i32,
@@ -60,8 +61,27 @@ impl<T: Copy + Default + Sized> Buffer<T> {
     pub fn as_slice<'b>(&'b self) -> &'b [T] {
         unsafe { std::slice::from_raw_parts(self.data, self.width as usize * self.height as usize) }
     }
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.width as usize * self.height as usize) }
+    }
+    /// Keeps whatever overlaps between the old and the new size, the rest is zeroed.
     pub fn resize(&mut self, w: usize, h: usize) {
-        unimplemented!("Buffer::resize()")
+        let (w, h) = (w as i32, h as i32);
+        if w == self.width && h == self.height {
+            return;
+        }
+        let mut resized = Self::init(w, h);
+        let copy_w = self.width.min(w) as usize;
+        for y in 0..self.height.min(h) {
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    self.data.add((y * self.width) as usize),
+                    resized.data.add((y * w) as usize),
+                    copy_w,
+                );
+            }
+        }
+        std::mem::swap(self, &mut resized);
     }
 
     pub fn len(&self) -> i32 {
@@ -157,3 +177,77 @@ impl<T: Copy + Default + Sized> Buffer<T> {
         self.height
     }
 }
+
+impl Buffer<u32> {
+    /// Blends `value` into the pixel, its alpha scaled by `coverage` (0.0..1.0).
+    /// `Replace` only replaces fully covered pixels, partly covered ones blend normally.
+    pub fn blend_xy(&mut self, x: i32, y: i32, value: u32, coverage: f32, mode: BlendMode) {
+        if x >= self.width || x < 0 || y < 0 || y >= self.height || coverage <= 0.0 {
+            return;
+        }
+        let index = (y * self.width + x) as usize;
+        unsafe {
+            let dst = self.data.add(index);
+            let blended = match mode {
+                BlendMode::Replace if coverage >= 1.0 => value,
+                BlendMode::Replace => blend(scale_alpha(value, coverage), *dst, BlendMode::Normal),
+                _ if coverage >= 1.0 => blend(value, *dst, mode),
+                _ => blend(scale_alpha(value, coverage), *dst, mode),
+            };
+            dst.write(blended);
+        }
+    }
+
+    /// Blends `value` into the pixels `x_start..x_end` of row `y`.
+    pub fn blend_span(&mut self, y: i32, x_start: i32, x_end: i32, value: u32, mode: BlendMode) {
+        if y < 0 || y >= self.height {
+            return;
+        }
+        let x0 = x_start.max(0);
+        let x1 = x_end.min(self.width);
+        if x0 >= x1 {
+            return;
+        }
+        let row = unsafe {
+            std::slice::from_raw_parts_mut(
+                self.data.add((y * self.width + x0) as usize),
+                (x1 - x0) as usize,
+            )
+        };
+        let opaque = value >> 24 == 0xFF;
+        match mode {
+            BlendMode::Replace => row.fill(value),
+            BlendMode::Normal if opaque => row.fill(value),
+            _ => {
+                for p in row.iter_mut() {
+                    *p = blend(value, *p, mode);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Copy + Default> Clone for Buffer<T> {
+    fn clone(&self) -> Self {
+        let layout = Layout::array::<T>(self.cap).expect("Layout to be buildable");
+        // SECURITY: same layout as the buffer we copy from
+        let data = unsafe {
+            let data = std::alloc::alloc(layout) as *mut T;
+            std::ptr::copy_nonoverlapping(self.data, data, self.cap);
+            data
+        };
+        Self {
+            data,
+            width: self.width,
+            height: self.height,
+            cap: self.cap,
+        }
+    }
+}
+
+impl<T: Copy + Default> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        let layout = Layout::array::<T>(self.cap).expect("Layout to be buildable");
+        unsafe { std::alloc::dealloc(self.data as *mut u8, layout) }
+    }
+}
@@ -1,12 +1,14 @@
 pub mod color;
 pub mod modify;
+pub mod paint;
 pub mod presets;
 
 #[cfg(test)]
 mod test {
     use crate::colors::{
         color::{Color, ColorU32},
-        modify::{is_transparent, set_alpha, swap_alpha},
+        modify::{BlendMode, blend, is_transparent, set_alpha, swap_alpha},
+        paint::{Gradient, Spread},
         presets::GrapesColors,
     };
 
@@ -32,4 +34,36 @@ mod test {
         assert!(!is_transparent(base_opaque.into()));
         assert!(is_transparent(base_transparent.into()));
     }
+
+    #[test]
+    pub fn blend_modes() {
+        let bg = 0xFF804020;
+        assert_eq!(blend(0x80FFFFFF, bg, BlendMode::Normal), 0xFFBF9F8F);
+        assert_eq!(blend(0xFF404040, bg, BlendMode::Additive), 0xFFC08060);
+        assert_eq!(blend(0xFF808080, bg, BlendMode::Multiply), 0xFF402010);
+        assert_eq!(blend(0xFF808080, bg, BlendMode::Screen), 0xFFC0A090);
+        assert_eq!(blend(0x80FFFFFF, bg, BlendMode::Replace), 0x80FFFFFF);
+        assert_eq!(blend(0x00FFFFFF, bg, BlendMode::Additive), bg);
+        // onto a fully transparent destination the source keeps its color
+        assert_eq!(blend(0x80FF0000, 0x00000000, BlendMode::Normal), 0x80FF0000);
+    }
+
+    #[test]
+    fn gradient_stops_and_spread() {
+        let gradient = Gradient::new(&[
+            (1.0, 0xFF0000FFu32),
+            (0.0, 0xFF000000u32),
+            (0.5, 0xFFFFFFFFu32),
+        ]);
+        assert_eq!(gradient.stops()[1].offset, 0.5);
+        assert_eq!(gradient.color_at(0.25), Color::new(0x80, 0x80, 0x80, 0xFF));
+        assert_eq!(gradient.color_at(0.5), Color::new(0xFF, 0xFF, 0xFF, 0xFF));
+        assert_eq!(gradient.color_at(2.0), Color::new(0, 0, 0xFF, 0xFF));
+
+        let repeat = gradient.clone().with_spread(Spread::Repeat);
+        assert_eq!(repeat.color_at(1.25), gradient.color_at(0.25));
+        let reflect = gradient.with_spread(Spread::Reflect);
+        assert_eq!(reflect.color_at(1.5), reflect.color_at(0.5));
+        assert_eq!(reflect.color_at(1.75), reflect.color_at(0.25));
+    }
 }
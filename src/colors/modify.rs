@@ -14,6 +14,12 @@ pub fn swap_alpha(v: u32) -> u32 {
     value | a << 24
 }
 
+///x11 order is expected, `factor` is clamped to 0.0..1.0
+pub fn scale_alpha(v: u32, factor: f32) -> u32 {
+    let a = (v >> (8 * 3) & 0xFF) as f32 * factor.clamp(0.0, 1.0);
+    set_alpha(v, a.round() as u8)
+}
+
 /// assumes x11 order
 pub fn is_transparent(c: u32) -> bool {
     (c >> (8 * 3) & 0xFF) as u8 == 0
@@ -40,3 +46,47 @@ pub fn alpha_blend(fg: u32, bg: u32) -> u32 {
 
     b | g << 8 | r << 16 | a << 24
 }
+
+/// How a color gets combined with what is already in the buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    /// regular "over" blending by source alpha
+    #[default]
+    Normal,
+    /// adds the source, weighted by its alpha, brightens
+    Additive,
+    /// multiplies with the destination, darkens
+    Multiply,
+    /// inverse of multiply, brightens
+    Screen,
+    /// writes the source as is, alpha included
+    Replace,
+}
+
+///x11 order is expected for both arguments
+pub fn blend(fg: u32, bg: u32, mode: BlendMode) -> u32 {
+    if mode == BlendMode::Replace {
+        return fg;
+    }
+    let fg_a = fg >> (8 * 3) & 0xFF;
+    if fg_a == 0 {
+        return bg;
+    }
+    let bg_a = bg >> (8 * 3) & 0xFF;
+    let out_a = fg_a + bg_a * (255 - fg_a) / 255;
+
+    let channel = |shift: u32| {
+        let s = fg >> shift & 0xFF;
+        let d = bg >> shift & 0xFF;
+        let mixed = match mode {
+            BlendMode::Normal | BlendMode::Replace => s,
+            BlendMode::Additive => (s + d).min(255),
+            BlendMode::Multiply => s * d / 255,
+            BlendMode::Screen => 255 - (255 - s) * (255 - d) / 255,
+        };
+        // source over destination, the destination weighted by its own alpha
+        (mixed * fg_a * 255 + d * bg_a * (255 - fg_a)) / (out_a * 255)
+    };
+
+    channel(0) | channel(8) << 8 | channel(16) << 16 | out_a << 24
+}
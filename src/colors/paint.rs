@@ -0,0 +1,277 @@
+use crate::{
+    linal::vertx2::VX2,
+    objects::utils::Bounds,
+    textures::{
+        Texture,
+        blit::{Sampling, sample},
+    },
+    vx2,
+};
+
+use super::color::Color;
+
+/// What a gradient or pattern does outside of 0.0..1.0 (or the texture).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Spread {
+    /// keeps the color at the closest end
+    #[default]
+    Pad,
+    /// starts over
+    Repeat,
+    /// goes back and forth
+    Reflect,
+}
+
+impl Spread {
+    /// Maps `t` into 0.0..=1.0.
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Spread::Pad => t.clamp(0.0, 1.0),
+            Spread::Repeat => t.rem_euclid(1.0),
+            Spread::Reflect => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// 0.0..=1.0 along the gradient
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// Colors along 0.0..1.0, interpolated between the stops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<ColorStop>,
+    pub spread: Spread,
+}
+
+impl Gradient {
+    /// Stops as (offset, color), in any order.
+    pub fn new<C: Into<Color> + Copy>(stops: &[(f32, C)]) -> Self {
+        let mut stops: Vec<ColorStop> = stops
+            .iter()
+            .map(|(offset, color)| ColorStop {
+                offset: offset.clamp(0.0, 1.0),
+                color: (*color).into(),
+            })
+            .collect();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Self {
+            stops,
+            spread: Spread::default(),
+        }
+    }
+    /// From one color to another.
+    pub fn two<C: Into<Color> + Copy>(from: C, to: C) -> Self {
+        Self::new(&[(0.0, from), (1.0, to)])
+    }
+    pub fn with_spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    /// Color at `t`, spread applied first.
+    pub fn color_at(&self, t: f32) -> Color {
+        let t = self.spread.apply(t);
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return Color::default();
+        };
+        if t <= first.offset {
+            return first.color;
+        }
+        if t >= last.offset {
+            return last.color;
+        }
+        let i = self.stops.partition_point(|s| s.offset <= t);
+        let (a, b) = (&self.stops[i - 1], &self.stops[i]);
+        let span = b.offset - a.offset;
+        if span <= 0.0 {
+            return b.color;
+        }
+        Color::lerp(&a.color, &b.color, (t - a.offset) / span)
+    }
+}
+
+/// A texture tiled over the plane.
+#[derive(Clone, Copy)]
+pub struct Pattern<'a> {
+    pub texture: &'a Texture,
+    /// where the top left corner of the texture is
+    pub offset: VX2,
+    /// size of one texel
+    pub scale: VX2,
+    pub spread: Spread,
+    pub sampling: Sampling,
+}
+
+impl<'a> Pattern<'a> {
+    /// Repeats the texture, unscaled, from 0, 0.
+    pub fn new(texture: &'a Texture) -> Self {
+        Self {
+            texture,
+            offset: VX2::default(),
+            scale: vx2!(1.0, 1.0),
+            spread: Spread::Repeat,
+            sampling: Sampling::Nearest,
+        }
+    }
+    pub fn with_offset(mut self, offset: VX2) -> Self {
+        self.offset = offset;
+        self
+    }
+    pub fn with_scale(mut self, sx: f32, sy: f32) -> Self {
+        self.scale = vx2!(sx, sy);
+        self
+    }
+    pub fn with_spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    fn color_at(&self, p: &VX2) -> u32 {
+        let size = self.texture.size();
+        if size.x < 1.0 || size.y < 1.0 || self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return 0;
+        }
+        let u = (p.x - self.offset.x) / (self.scale.x * size.x);
+        let v = (p.y - self.offset.y) / (self.scale.y * size.y);
+        let src = Bounds::from_rect(0.0, 0.0, size.x, size.y);
+        sample(
+            self.texture.get_buffer(),
+            &src,
+            self.spread.apply(u) * size.x,
+            self.spread.apply(v) * size.y,
+            self.sampling,
+        )
+    }
+}
+
+/// What fills get colored with. Positions are in the same (model) coordinates as the shapes,
+/// so gradients and patterns move along with the renderer transform and camera.
+#[derive(Clone)]
+pub enum Paint<'a> {
+    Solid(Color),
+    /// 0.0 at `start`, 1.0 at `end`, constant across
+    Linear {
+        start: VX2,
+        end: VX2,
+        gradient: Gradient,
+    },
+    /// 0.0 at `center`, 1.0 at `radius` away from it
+    Radial {
+        center: VX2,
+        radius: f32,
+        gradient: Gradient,
+    },
+    /// 0.0 to 1.0 once around `center`, clockwise from `angle` degrees
+    Conic {
+        center: VX2,
+        angle: f32,
+        gradient: Gradient,
+    },
+    Pattern(Pattern<'a>),
+}
+
+impl<'a> Paint<'a> {
+    pub fn solid<C: Into<Color>>(c: C) -> Self {
+        Paint::Solid(c.into())
+    }
+    pub fn linear(start: VX2, end: VX2, gradient: Gradient) -> Self {
+        Paint::Linear {
+            start,
+            end,
+            gradient,
+        }
+    }
+    pub fn radial(center: VX2, radius: f32, gradient: Gradient) -> Self {
+        Paint::Radial {
+            center,
+            radius,
+            gradient,
+        }
+    }
+    pub fn conic(center: VX2, angle: f32, gradient: Gradient) -> Self {
+        Paint::Conic {
+            center,
+            angle,
+            gradient,
+        }
+    }
+    pub fn pattern(texture: &'a Texture) -> Self {
+        Paint::Pattern(Pattern::new(texture))
+    }
+
+    /// The color if it is the same everywhere.
+    pub fn as_solid(&self) -> Option<Color> {
+        match self {
+            Paint::Solid(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// x11 ARGB color at `p`, in paint coordinates.
+    pub fn color_at(&self, p: &VX2) -> u32 {
+        match self {
+            Paint::Solid(c) => (*c).into(),
+            Paint::Linear {
+                start,
+                end,
+                gradient,
+            } => {
+                let dir = *end - start;
+                let len_sq = dir.dot(&dir);
+                let t = if len_sq > 0.0 {
+                    (*p - start).dot(&dir) / len_sq
+                } else {
+                    0.0
+                };
+                gradient.color_at(t).into()
+            }
+            Paint::Radial {
+                center,
+                radius,
+                gradient,
+            } => {
+                let t = if *radius > 0.0 {
+                    (*p - center).length() / radius
+                } else {
+                    1.0
+                };
+                gradient.color_at(t).into()
+            }
+            Paint::Conic {
+                center,
+                angle,
+                gradient,
+            } => {
+                let deg = (p.y - center.y).atan2(p.x - center.x).to_degrees() - angle;
+                gradient.color_at(deg.rem_euclid(360.0) / 360.0).into()
+            }
+            Paint::Pattern(pattern) => pattern.color_at(p),
+        }
+    }
+}
+
+impl<'a> From<Color> for Paint<'a> {
+    fn from(value: Color) -> Self {
+        Paint::Solid(value)
+    }
+}
+
+impl<'a> From<Pattern<'a>> for Paint<'a> {
+    fn from(value: Pattern<'a>) -> Self {
+        Paint::Pattern(value)
+    }
+}
@@ -79,4 +79,86 @@ impl TimeInfo {
     pub fn delta_time_s(&self) -> f64 {
         self.delta_time.as_millis() as f64 / 1000.0
     }
+
+    pub fn delta(&self) -> Duration {
+        self.delta_time
+    }
+}
+
+const DEFAULT_MAX_STEPS: u32 = 8;
+
+/// Accumulator for running the simulation at a fixed tick rate,
+/// independent of how fast frames are drawn.
+#[derive(Debug, Clone)]
+pub struct FixedStep {
+    step: Duration,
+    accumulator: Duration,
+    max_steps: u32,
+}
+
+impl FixedStep {
+    pub fn new(tick_rate: u32) -> Self {
+        Self {
+            step: Duration::from_secs_f64(1.0 / tick_rate.max(1) as f64),
+            accumulator: Duration::ZERO,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+
+    /// Upper bound of ticks per frame. Whatever time is left above that gets
+    /// dropped, so a slow frame can't make the next one even slower.
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    pub fn step_s(&self) -> f32 {
+        self.step.as_secs_f32()
+    }
+
+    /// Adds the frame time and returns how many ticks to run.
+    pub fn advance(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta.min(self.step * self.max_steps);
+        let mut ticks = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// How far we are into the next tick, 0.0 to 1.0. Used to interpolate
+    /// between the previous and the current simulation state when rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::context::time::FixedStep;
+
+    #[test]
+    fn fixed_step_accumulates() {
+        let mut fixed = FixedStep::new(100);
+        assert_eq!(fixed.advance(Duration::from_millis(5)), 0);
+        assert!((fixed.alpha() - 0.5).abs() < 0.01);
+        assert_eq!(fixed.advance(Duration::from_millis(5)), 1);
+        assert_eq!(fixed.advance(Duration::from_millis(35)), 3);
+        assert!((fixed.alpha() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn fixed_step_clamps() {
+        let mut fixed = FixedStep::new(100).with_max_steps(4);
+        assert_eq!(fixed.advance(Duration::from_secs(2)), 4);
+        assert!(fixed.alpha() < 0.01);
+        assert_eq!(fixed.advance(Duration::from_millis(10)), 1);
+    }
 }
@@ -1,25 +1,323 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::linal::vertx2::VX2;
+use crate::linal::{mat3::Mat3, vertx2::VX2};
+use crate::objects::utils::Bounds;
+use crate::utils::lerp_vx2;
 use crate::vx2;
 
 pub type Camera2dRef = Rc<RefCell<Camera2d>>;
 
-#[derive(Debug, Default)] 
+const DEFAULT_TRAUMA_DECAY: f32 = 1.0;
+const DEFAULT_SHAKE_OFFSET: f32 = 16.0;
+const DEFAULT_SHAKE_ANGLE: f32 = 5.0;
+const SHAKE_FREQUENCY: f32 = 25.0;
+
+/// `position` is the world point shown in the middle of the viewport.
+/// Rotation is in degrees, zoom > 1.0 magnifies. The default camera stays on the middle of
+/// the viewport until it gets moved, so world and screen coordinates match.
+#[derive(Debug)]
 pub struct Camera2d {
     position: VX2,
+    zoom: f32,
+    rotation: f32,
+    viewport: VX2,
+    /// `position` follows the viewport size, top left of the world at the top left of the screen
+    screen_aligned: bool,
+    target: Option<VX2>,
+    /// world units the target can move around the center before the camera follows
+    deadzone: VX2,
+    /// 0.0 snaps to the target, higher values catch up faster
+    smoothing: f32,
+    bounds: Option<Bounds>,
+    trauma: f32,
+    trauma_decay: f32,
+    max_shake_offset: f32,
+    max_shake_angle: f32,
+    shake_time: f32,
+    shake_offset: VX2,
+    shake_angle: f32,
+}
+
+impl Default for Camera2d {
+    fn default() -> Self {
+        Self {
+            position: VX2::default(),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: VX2::default(),
+            screen_aligned: true,
+            target: None,
+            deadzone: VX2::default(),
+            smoothing: 0.0,
+            bounds: None,
+            trauma: 0.0,
+            trauma_decay: DEFAULT_TRAUMA_DECAY,
+            max_shake_offset: DEFAULT_SHAKE_OFFSET,
+            max_shake_angle: DEFAULT_SHAKE_ANGLE,
+            shake_time: 0.0,
+            shake_offset: VX2::default(),
+            shake_angle: 0.0,
+        }
+    }
 }
 
 impl Camera2d {
     pub fn new(x: f32, y: f32) -> Self {
         Self {
             position: vx2!(x, y),
+            screen_aligned: false,
+            ..Default::default()
         }
     }
+    /// Camera looking at the center of a `w` x `h` viewport, screen and world coordinates match.
     pub fn center(w: f32, h: f32) -> Self {
         Self {
-            position: vx2!(w*0.5, h*0.5)
+            position: vx2!(w * 0.5, h * 0.5),
+            viewport: vx2!(w, h),
+            ..Default::default()
+        }
+    }
+
+    pub fn position(&self) -> VX2 {
+        self.position
+    }
+    pub fn set_position(&mut self, position: VX2) {
+        self.position = position;
+        self.screen_aligned = false;
+        self.clamp_to_bounds();
+    }
+    pub fn move_by(&mut self, offset: &VX2) {
+        self.position += offset;
+        self.screen_aligned = false;
+        self.clamp_to_bounds();
+    }
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+        self.clamp_to_bounds();
+    }
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+    pub fn set_rotation(&mut self, deg: f32) {
+        self.rotation = deg;
+    }
+    pub fn viewport(&self) -> VX2 {
+        self.viewport
+    }
+    /// Done by the engine on startup and whenever the window is resized.
+    pub fn set_viewport(&mut self, w: f32, h: f32) {
+        self.viewport = vx2!(w, h);
+        if self.screen_aligned {
+            self.position = self.viewport * 0.5;
         }
+        self.clamp_to_bounds();
+    }
+
+    pub fn follow(&mut self, target: VX2) {
+        self.target = Some(target);
+        self.screen_aligned = false;
+    }
+    pub fn stop_follow(&mut self) {
+        self.target = None;
+    }
+    pub fn set_deadzone(&mut self, size: VX2) {
+        self.deadzone = size;
+    }
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.max(0.0);
+    }
+    /// The camera never shows anything outside of `bounds`.
+    pub fn set_bounds(&mut self, bounds: Option<Bounds>) {
+        self.bounds = bounds;
+        self.clamp_to_bounds();
+    }
+
+    /// Trauma is clamped to 0.0..1.0, the shake grows with trauma squared.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+    /// Trauma lost per second.
+    pub fn set_trauma_decay(&mut self, decay: f32) {
+        self.trauma_decay = decay;
+    }
+    /// Shake at full trauma, offset in pixels and angle in degrees.
+    pub fn set_max_shake(&mut self, offset: f32, angle: f32) {
+        self.max_shake_offset = offset;
+        self.max_shake_angle = angle;
+    }
+
+    /// Moves towards the follow target and advances the shake. The engine calls this once per frame.
+    pub fn update(&mut self, dt: f32) {
+        if let Some(target) = self.target {
+            let half = self.deadzone * 0.5;
+            let mut desired = self.position;
+            let delta = target - &self.position;
+            if delta.x > half.x {
+                desired.x = target.x - half.x;
+            } else if delta.x < -half.x {
+                desired.x = target.x + half.x;
+            }
+            if delta.y > half.y {
+                desired.y = target.y - half.y;
+            } else if delta.y < -half.y {
+                desired.y = target.y + half.y;
+            }
+            self.position = if self.smoothing > 0.0 {
+                lerp_vx2(&self.position, &desired, 1.0 - (-self.smoothing * dt).exp())
+            } else {
+                desired
+            };
+            self.clamp_to_bounds();
+        }
+
+        self.trauma = (self.trauma - self.trauma_decay * dt).max(0.0);
+        self.shake_time += dt;
+        let shake = self.trauma * self.trauma;
+        let t = self.shake_time * SHAKE_FREQUENCY;
+        self.shake_offset = vx2!(noise(t, 0.0), noise(t, 31.7)) * (self.max_shake_offset * shake);
+        self.shake_angle = noise(t, 73.1) * self.max_shake_angle * shake;
+    }
+
+    /// Shake offset in screen pixels and angle in degrees of the current frame.
+    pub fn shake(&self) -> (VX2, f32) {
+        (self.shake_offset, self.shake_angle)
+    }
+
+    pub fn world_to_screen(&self, p: &VX2) -> VX2 {
+        let d = rotate(&(p - &self.position), -(self.rotation + self.shake_angle));
+        d * self.zoom + &(self.viewport * 0.5) + &self.shake_offset
+    }
+
+    pub fn screen_to_world(&self, p: &VX2) -> VX2 {
+        let d = (*p - &(self.viewport * 0.5) - &self.shake_offset) / self.zoom;
+        rotate(&d, self.rotation + self.shake_angle) + &self.position
+    }
+
+    /// Same mapping as [`Camera2d::world_to_screen`] as a matrix.
+    pub fn view_matrix(&self) -> Mat3 {
+        let center = self.viewport * 0.5 + &self.shake_offset;
+        Mat3::translation(center.x, center.y)
+            * Mat3::scale(self.zoom, self.zoom)
+            * Mat3::rotation(-(self.rotation + self.shake_angle))
+            * Mat3::translation(-self.position.x, -self.position.y)
+    }
+
+    /// Length in world units to length on screen.
+    pub fn world_to_screen_scale(&self, len: f32) -> f32 {
+        len * self.zoom
+    }
+
+    fn clamp_to_bounds(&mut self) {
+        let Some(b) = self.bounds else {
+            return;
+        };
+        let half = self.viewport * (0.5 / self.zoom);
+        self.position.x = clamp_axis(self.position.x, b.min_x, b.max_x, half.x);
+        self.position.y = clamp_axis(self.position.y, b.min_y, b.max_y, half.y);
+    }
+}
+
+fn clamp_axis(v: f32, min: f32, max: f32, half_view: f32) -> f32 {
+    if max - min <= half_view * 2.0 {
+        (min + max) * 0.5
+    } else {
+        v.clamp(min + half_view, max - half_view)
+    }
+}
+
+fn rotate(v: &VX2, deg: f32) -> VX2 {
+    let (s, c) = deg.to_radians().sin_cos();
+    vx2!(v.x * c - v.y * s, v.x * s + v.y * c)
+}
+
+/// Smooth-ish value in -1.0..1.0, good enough for shaking.
+fn noise(t: f32, seed: f32) -> f32 {
+    ((t + seed).sin() + (t * 2.3 + seed * 1.3).sin() * 0.5 + (t * 4.7 + seed * 0.7).sin() * 0.25)
+        / 1.75
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{engine::camera_2d::Camera2d, objects::utils::Bounds, vx2};
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.01
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut cam = Camera2d::center(200.0, 100.0);
+        let p = vx2!(30.0, 70.0);
+        let s = cam.world_to_screen(&p);
+        assert!(close(s.x, 30.0) && close(s.y, 70.0));
+
+        cam.set_zoom(2.0);
+        cam.set_rotation(33.0);
+        cam.set_position(vx2!(10.0, -5.0));
+        let back = cam.screen_to_world(&cam.world_to_screen(&p));
+        assert!(close(back.x, p.x) && close(back.y, p.y));
+        let (a, b) = (
+            cam.world_to_screen(&p),
+            cam.view_matrix().transform_point(&p),
+        );
+        assert!(close(a.x, b.x) && close(a.y, b.y));
+        let center = cam.world_to_screen(&vx2!(10.0, -5.0));
+        assert!(close(center.x, 100.0) && close(center.y, 50.0));
+    }
+
+    #[test]
+    fn default_is_screen_aligned() {
+        let mut cam = Camera2d::default();
+        cam.set_viewport(200.0, 100.0);
+        let s = cam.world_to_screen(&vx2!(30.0, 70.0));
+        assert!(close(s.x, 30.0) && close(s.y, 70.0));
+        // still after a resize, until it gets moved
+        cam.set_viewport(300.0, 50.0);
+        let s = cam.world_to_screen(&vx2!(30.0, 70.0));
+        assert!(close(s.x, 30.0) && close(s.y, 70.0));
+        cam.move_by(&vx2!(10.0, 0.0));
+        cam.set_viewport(100.0, 50.0);
+        assert!(close(cam.position().x, 160.0));
+    }
+
+    #[test]
+    fn follow_with_deadzone_and_bounds() {
+        let mut cam = Camera2d::center(100.0, 100.0);
+        cam.set_deadzone(vx2!(20.0, 20.0));
+        cam.follow(vx2!(55.0, 50.0));
+        cam.update(0.016);
+        assert!(close(cam.position().x, 50.0));
+        cam.follow(vx2!(80.0, 50.0));
+        cam.update(0.016);
+        assert!(close(cam.position().x, 70.0));
+
+        cam.set_bounds(Some(Bounds {
+            min_x: 0.0,
+            max_x: 120.0,
+            min_y: 0.0,
+            max_y: 100.0,
+        }));
+        cam.follow(vx2!(500.0, 50.0));
+        cam.update(0.016);
+        assert!(close(cam.position().x, 70.0));
+    }
+
+    #[test]
+    fn trauma_decays() {
+        let mut cam = Camera2d::center(100.0, 100.0);
+        cam.add_trauma(0.5);
+        cam.update(0.25);
+        assert!(close(cam.trauma(), 0.25));
+        cam.update(1.0);
+        assert_eq!(cam.trauma(), 0.0);
+        let (offset, angle) = cam.shake();
+        assert_eq!((offset.x, offset.y, angle), (0.0, 0.0, 0.0));
     }
 }
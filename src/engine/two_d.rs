@@ -1,19 +1,25 @@
+use std::time::Duration;
+
 use crate::{
-    context::time::TimeInfo,
+    backend::{Backend, headless::Headless},
+    context::time::{FixedStep, TimeInfo},
     error::{ErrorX, GrapesError, ResultGrapes},
-    events::input::Events,
+    events::input::{Events, KeyEdges},
     internal::window::Window,
-    renderer::two_d::Renderer,
+    renderer::two_d::{Renderer, ResizePolicy},
     state::two_d::State,
 };
 
 use super::camera_2d::Camera2dRef;
 
 pub struct Engine<S: State> {
-    w: Window,
+    w: Box<dyn Backend>,
     state: S,
     renderer: Renderer,
     time: TimeInfo,
+    fixed_step: Option<FixedStep>,
+    /// key presses and releases waiting for the next fixed tick
+    edges: KeyEdges,
     camera: Camera2dRef,
 }
 
@@ -26,26 +32,60 @@ impl<S: State> Engine<S> {
     ) -> ResultGrapes<Self> {
         let w = Window::new(title, width, height)
             .map_err(|err| <ErrorX as Into<GrapesError>>::into(err))?;
-        let mut renderer = Renderer::new(width, height);
+        Ok(Self::with_backend(Box::new(w), camera))
+    }
+
+    /// Same loop as [`Engine::create_window`], but without a display.
+    pub fn create_headless(headless: Headless, camera: Camera2dRef) -> ResultGrapes<Self> {
+        Ok(Self::with_backend(Box::new(headless), camera))
+    }
+
+    fn with_backend(w: Box<dyn Backend>, camera: Camera2dRef) -> Self {
+        let (width, height) = w.get_size();
+        let mut renderer = Renderer::new(width as usize, height as usize);
+        camera
+            .borrow_mut()
+            .set_viewport(width as f32, height as f32);
+        renderer.set_camera(Some(camera.clone()));
         let state = S::user_init(&mut renderer, camera.clone());
         let target_fps = w.get_fps();
-        Ok(Self {
+        Self {
             w,
             state,
             renderer,
             time: TimeInfo::new(target_fps as u64),
+            fixed_step: None,
+            edges: KeyEdges::default(),
             camera,
-        })
+        }
     }
 
     pub fn set_target_fps(&mut self, fps: usize) {
         self.w.set_target_fps(fps);
     }
 
+    /// `Some` switches to `State::fixed_update` + `State::render`,
+    /// `None` goes back to `State::user_update`.
+    pub fn set_fixed_timestep(&mut self, fixed_step: Option<FixedStep>) {
+        self.fixed_step = fixed_step;
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.w.set_title(title);
+    }
+
     pub fn camera(&mut self) -> Camera2dRef {
         self.camera.clone()
     }
 
+    pub fn renderer(&self) -> &Renderer {
+        &self.renderer
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
     pub fn run(&mut self) -> ResultGrapes<()> {
         self.time.start();
         loop {
@@ -53,21 +93,144 @@ impl<S: State> Engine<S> {
                 break;
             }
             self.time.update();
-            let events = Events::new(&self.w);
-            if self
-                .state
-                .user_update(&mut self.renderer, events, self.camera.clone(), &self.time)
-            {
+            if self.frame(self.time.delta())? {
                 break;
             }
-            let buf = self.renderer.buffer();
-            self.w.update_with_buffer_stride(
-                buf.as_slice(),
-                buf.width() as usize,
-                buf.height() as usize,
-                buf.width() as usize,
-            )?;
         }
         Ok(())
     }
+
+    /// Updates, draws and presents one frame that took `delta`, true means quit.
+    pub(crate) fn frame(&mut self, delta: Duration) -> ResultGrapes<bool> {
+        self.camera.borrow_mut().update(delta.as_secs_f32());
+        if self.step(delta) {
+            return Ok(true);
+        }
+        let buf = self.renderer.buffer();
+        self.w.update_with_buffer_stride(
+            buf.as_slice(),
+            buf.width() as usize,
+            buf.height() as usize,
+            buf.width() as usize,
+            self.renderer.resize_policy(),
+        )?;
+        if let Some((width, height)) = self.w.take_resize() {
+            self.handle_resize(width as usize, height as usize);
+        }
+        Ok(false)
+    }
+
+    /// Runs the user callbacks for one frame, true means quit.
+    fn step(&mut self, delta: Duration) -> bool {
+        let Some(fixed) = self.fixed_step.as_mut() else {
+            let events = Events::new(self.w.as_ref()).with_camera(self.camera.clone());
+            return self.state.user_update(
+                &mut self.renderer,
+                events,
+                self.camera.clone(),
+                &self.time,
+            );
+        };
+        let ticks = fixed.advance(delta);
+        let dt = fixed.step_s();
+        let alpha = fixed.alpha();
+        // presses and releases go to the first tick that runs, later ticks of the frame
+        // only see what is held down
+        self.edges.collect(self.w.as_ref());
+        let no_edges = KeyEdges::default();
+        for tick in 0..ticks {
+            let edges = if tick == 0 { &self.edges } else { &no_edges };
+            let events = Events::new(self.w.as_ref())
+                .with_camera(self.camera.clone())
+                .with_edges(edges);
+            if self.state.fixed_update(&events, self.camera.clone(), dt) {
+                return true;
+            }
+        }
+        if ticks > 0 {
+            self.edges.clear();
+        }
+        self.state
+            .render(&mut self.renderer, self.camera.clone(), alpha);
+        false
+    }
+
+    fn handle_resize(&mut self, width: usize, height: usize) {
+        // minimized, keep everything at the last real size
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.camera
+            .borrow_mut()
+            .set_viewport(width as f32, height as f32);
+        if self.renderer.resize_policy() == ResizePolicy::Follow {
+            self.renderer.resize(width, height);
+        }
+        self.state.user_resize(&mut self.renderer, width, height);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        backend::headless::{EventScript, Headless, ScriptedEvent},
+        context::time::{FixedStep, TimeInfo},
+        engine::{camera_2d::Camera2dRef, two_d::Engine},
+        events::{input::Events, keyboard::K},
+        renderer::two_d::Renderer,
+        state::two_d::State,
+    };
+
+    #[derive(Default)]
+    struct Ticks {
+        ticks: u32,
+        pressed: Vec<u32>,
+        released: Vec<u32>,
+    }
+
+    impl State for Ticks {
+        fn user_init(_renderer: &mut Renderer, _camera: Camera2dRef) -> Self {
+            Self::default()
+        }
+        fn user_update(
+            &mut self,
+            _renderer: &mut Renderer,
+            _events: Events,
+            _camera: Camera2dRef,
+            _time_info: &TimeInfo,
+        ) -> bool {
+            false
+        }
+        fn fixed_update(&mut self, events: &Events, _camera: Camera2dRef, _dt: f32) -> bool {
+            if events.key_pressed(K::Space) {
+                self.pressed.push(self.ticks);
+            }
+            if events.key_released(K::Space) {
+                self.released.push(self.ticks);
+            }
+            self.ticks += 1;
+            false
+        }
+    }
+
+    #[test]
+    fn key_edges_reach_one_tick() {
+        let script = EventScript::new()
+            .at(1, ScriptedEvent::KeyDown(K::Space))
+            .at(4, ScriptedEvent::KeyUp(K::Space))
+            .at(5, ScriptedEvent::KeyDown(K::Space));
+        let backend = Headless::new(8, 8).with_script(script);
+        let mut engine = Engine::<Ticks>::create_headless(backend, Default::default()).unwrap();
+        engine.set_fixed_timestep(Some(FixedStep::new(10)));
+        // 100ms ticks: the press seen on frame 2 has no tick and waits for the three of
+        // frame 3, the release of frame 4 waits through frame 5 for the press of frame 6
+        for ms in [0, 0, 50, 300, 0, 0, 300] {
+            engine.frame(Duration::from_millis(ms)).unwrap();
+        }
+        assert_eq!(engine.state().ticks, 6);
+        assert_eq!(engine.state().pressed, vec![0, 3]);
+        assert_eq!(engine.state().released, vec![3]);
+    }
 }
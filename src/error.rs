@@ -24,6 +24,8 @@ pub enum GrapesError {
     LoadingResource(String),
     #[error("Parsing resource {0}")]
     ParsingResource(String),
+    #[error("Saving resource {0}")]
+    SavingResource(String),
     #[error("Unsupported: {0}")]
     Unsupported(String),
     #[error("Illegal Conversion: {0}")]
@@ -1,14 +1,61 @@
-use crate::{internal::window::Window, linal::vertx2::VX2, vx2};
+use crate::{backend::Backend, engine::camera_2d::Camera2dRef, linal::vertx2::VX2, vx2};
 
 use super::{keyboard::K, mouse::MouseButton};
 
+/// Keys pressed or released since the last fixed tick. Held by the engine so presses on
+/// frames without a tick aren't lost and catch-up ticks don't see them twice.
+#[derive(Clone)]
+pub(crate) struct KeyEdges {
+    pressed: [bool; K::Count as usize],
+    released: [bool; K::Count as usize],
+}
+
+impl Default for KeyEdges {
+    fn default() -> Self {
+        Self {
+            pressed: [false; K::Count as usize],
+            released: [false; K::Count as usize],
+        }
+    }
+}
+
+impl KeyEdges {
+    /// Adds the edges of the current frame to the ones held.
+    pub(crate) fn collect(&mut self, w: &dyn Backend) {
+        for k in K::ALL {
+            self.pressed[k as usize] |= w.key_pressed(k);
+            self.released[k as usize] |= w.key_released(k);
+        }
+    }
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
 pub struct Events<'w> {
-    inner: &'w Window,
+    inner: &'w dyn Backend,
+    camera: Option<Camera2dRef>,
+    /// answers `key_pressed` and `key_released` instead of the backend if set
+    edges: Option<&'w KeyEdges>,
 }
 
 impl<'w> Events<'w> {
-    pub fn new(w: &'w Window) -> Self {
-        Self { inner: w }
+    pub fn new(w: &'w dyn Backend) -> Self {
+        Self {
+            inner: w,
+            camera: None,
+            edges: None,
+        }
+    }
+
+    pub fn with_camera(mut self, camera: Camera2dRef) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    pub(crate) fn with_edges(mut self, edges: &'w KeyEdges) -> Self {
+        self.edges = Some(edges);
+        self
     }
 
     pub fn get_window_size(&self) -> VX2 {
@@ -24,15 +71,27 @@ impl<'w> Events<'w> {
             .get_mouse_position()
             .map(|pos| vx2!(pos.0, pos.1))
     }
+    /// Mouse position run through the camera, `None` without one.
+    pub fn get_mouse_world_position(&self) -> Option<VX2> {
+        let camera = self.camera.as_ref()?;
+        let pos = self.get_mouse_position()?;
+        Some(camera.borrow().screen_to_world(&pos))
+    }
 
     pub fn key_pressed(&self, k: K) -> bool {
-        self.inner.key_pressed(k)
+        match self.edges {
+            Some(edges) => edges.pressed[k as usize],
+            None => self.inner.key_pressed(k),
+        }
     }
     pub fn key_down(&self, k: K) -> bool {
         self.inner.key_down(k)
     }
     pub fn key_released(&self, k: K) -> bool {
-        self.inner.key_released(k)
+        match self.edges {
+            Some(edges) => edges.released[k as usize],
+            None => self.inner.key_released(k),
+        }
     }
     pub fn button_is_down(&self, b: MouseButton) -> bool {
         self.inner.button_is_down(b as usize)
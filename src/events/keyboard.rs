@@ -88,3 +88,90 @@ pub enum K {
     Count = 82,
 }
 
+impl K {
+    /// Every key but `Count`, in discriminant order.
+    pub const ALL: [K; K::Count as usize] = [
+        K::K0,
+        K::K1,
+        K::K2,
+        K::K3,
+        K::K4,
+        K::K5,
+        K::K6,
+        K::K7,
+        K::K8,
+        K::K9,
+        K::F1,
+        K::F2,
+        K::F3,
+        K::F4,
+        K::F5,
+        K::F6,
+        K::F7,
+        K::F8,
+        K::F9,
+        K::F10,
+        K::F11,
+        K::F12,
+        K::A,
+        K::B,
+        K::C,
+        K::D,
+        K::E,
+        K::F,
+        K::G,
+        K::H,
+        K::I,
+        K::J,
+        K::K,
+        K::L,
+        K::M,
+        K::N,
+        K::O,
+        K::P,
+        K::Q,
+        K::R,
+        K::S,
+        K::T,
+        K::U,
+        K::V,
+        K::W,
+        K::X,
+        K::Y,
+        K::Z,
+        K::Quote,
+        K::Comma,
+        K::Dash,
+        K::Period,
+        K::ForwardSlash,
+        K::SemiColon,
+        K::LessThan,
+        K::Equal,
+        K::SquareOpen,
+        K::BackSlash,
+        K::SquareClose,
+        K::BackTick,
+        K::ArrowLeft,
+        K::ArrowUp,
+        K::ArrowDown,
+        K::ArrowRight,
+        K::Space,
+        K::BackSpace,
+        K::Tab,
+        K::Enter,
+        K::Delete,
+        K::CapsLock,
+        K::LeftShift,
+        K::RightShift,
+        K::LeftCtrl,
+        K::RightCtrl,
+        K::Mod,
+        K::Alt,
+        K::RightAlt,
+        K::Home,
+        K::End,
+        K::Print,
+        K::Escape,
+        K::Unknown,
+    ];
+}
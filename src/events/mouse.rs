@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(usize)]
 pub enum MouseButton {
     Left = 0,
@@ -0,0 +1,378 @@
+//! Fonts drawn out of textures: AngelCode BMFont files and fixed grid sprite fonts.
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    colors::color::Color,
+    error::{GrapesError, ResultG},
+    linal::vertx2::VX2,
+    objects::utils::Bounds,
+    renderer::two_d::Renderer,
+    textures::{Texture, decode::argb},
+    vx2,
+};
+
+use super::{
+    bmfont,
+    layout::{GlyphPosition, LineMetrics, TextLayout, ink_bounds},
+    text::TextFont,
+};
+
+/// Where a glyph is in the page textures, at the font's own size.
+#[derive(Debug, Clone, Copy)]
+pub struct BitmapGlyph {
+    pub src: Bounds,
+    /// index into the pages
+    pub page: usize,
+    /// top left of `src` from the pen position on the top of the line
+    pub offset: VX2,
+    pub advance: f32,
+}
+
+/// Glyphs are drawn scaled by the asked size over the font's own size, with the nearest
+/// texel and no smoothing, so whole multiples of it stay crisp.
+#[derive(Clone)]
+pub struct BitmapFont {
+    pages: Vec<Texture>,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kerning: HashMap<(char, char), f32>,
+    size: f32,
+    line_height: f32,
+    /// top of the line to the baseline
+    base: f32,
+    /// drawn instead of characters without a glyph
+    missing: Option<char>,
+}
+
+impl BitmapFont {
+    /// A `.fnt` file, text or binary, the page textures are loaded from next to it.
+    pub fn load<P: AsRef<Path>>(path: P) -> ResultG<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| GrapesError::LoadingResource(format!("{}: {e}", path.display())))?;
+        let desc = bmfont::parse(&bytes)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let pages = desc
+            .pages
+            .iter()
+            .map(|file| Texture::load(dir.join(file)))
+            .collect::<ResultG<Vec<_>>>()?;
+        Self::from_descriptor(desc, pages)
+    }
+
+    /// `.fnt` file contents and its pages in the order of their ids.
+    pub fn from_bytes(fnt: &[u8], pages: Vec<Texture>) -> ResultG<Self> {
+        Self::from_descriptor(bmfont::parse(fnt)?, pages)
+    }
+
+    fn from_descriptor(desc: bmfont::Descriptor, pages: Vec<Texture>) -> ResultG<Self> {
+        if let Some((c, _)) = desc.glyphs.iter().find(|(_, g)| g.page >= pages.len()) {
+            return Err(GrapesError::ParsingResource(format!(
+                "BMFont: no page texture for '{c}'"
+            )));
+        }
+        let glyphs: HashMap<char, BitmapGlyph> = desc.glyphs.into_iter().collect();
+        Ok(Self {
+            pages,
+            missing: glyphs.contains_key(&'?').then_some('?'),
+            glyphs,
+            kerning: desc
+                .kerning
+                .into_iter()
+                .map(|(a, b, k)| ((a, b), k))
+                .collect(),
+            size: desc.size,
+            line_height: desc.line_height,
+            base: desc.base,
+        })
+    }
+
+    /// Cells of `cell_w` by `cell_h` pixels, left to right and top to bottom, hold the
+    /// glyphs of `chars` in that order. Characters past the last cell are left out.
+    pub fn grid(texture: Texture, cell_w: u32, cell_h: u32, chars: &str) -> Self {
+        let (cw, ch) = (cell_w.max(1) as f32, cell_h.max(1) as f32);
+        let columns = (texture.size().x / cw).floor().max(1.0) as usize;
+        let rows = (texture.size().y / ch).floor() as usize;
+        let glyphs: HashMap<char, BitmapGlyph> = chars
+            .chars()
+            .take(columns * rows)
+            .enumerate()
+            .map(|(i, c)| {
+                let (col, row) = ((i % columns) as f32, (i / columns) as f32);
+                let glyph = BitmapGlyph {
+                    src: Bounds::from_rect(col * cw, row * ch, cw, ch),
+                    page: 0,
+                    offset: vx2!(0.0, 0.0),
+                    advance: cw,
+                };
+                (c, glyph)
+            })
+            .collect();
+        Self {
+            pages: vec![texture],
+            missing: glyphs.contains_key(&'?').then_some('?'),
+            glyphs,
+            kerning: HashMap::new(),
+            size: ch,
+            line_height: ch,
+            base: ch,
+        }
+    }
+
+    /// Draws `c` for characters the font lacks, '?' by default if there is one.
+    pub fn with_missing(mut self, c: Option<char>) -> Self {
+        self.missing = c.filter(|c| self.glyphs.contains_key(c));
+        self
+    }
+
+    /// Pixel size the glyphs were made for.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+    pub fn pages(&self) -> &[Texture] {
+        &self.pages
+    }
+    pub fn glyph(&self, c: char) -> Option<&BitmapGlyph> {
+        self.glyphs.get(&c)
+    }
+
+    pub fn line_metrics(&self, size: f32) -> LineMetrics {
+        let scale = size / self.size;
+        LineMetrics {
+            ascent: self.base * scale,
+            descent: (self.line_height - self.base) * scale,
+            line_gap: 0.0,
+            line_height: self.line_height * scale,
+        }
+    }
+}
+
+impl TextFont for BitmapFont {
+    fn layout(&self, text: &str, size: f32) -> TextLayout {
+        let scale = size / self.size;
+        let metrics = self.line_metrics(size);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut line_widths = vec![0.0];
+        let (mut pen_x, mut line) = (0.0, 0);
+        let mut prev: Option<char> = None;
+
+        for (index, c) in text.char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_widths.push(0.0);
+                (pen_x, prev) = (0.0, None);
+                continue;
+            }
+            let c = match self.glyphs.contains_key(&c) {
+                true => c,
+                false if c.is_control() => continue,
+                false => match self.missing {
+                    Some(missing) => missing,
+                    None => continue,
+                },
+            };
+            let g = &self.glyphs[&c];
+            if let Some(kern) = prev.and_then(|p| self.kerning.get(&(p, c))) {
+                pen_x += kern * scale;
+            }
+            let advance = g.advance * scale;
+            glyphs.push(GlyphPosition {
+                c,
+                font: 0,
+                index,
+                line,
+                x: pen_x + g.offset.x * scale,
+                y: line as f32 * metrics.line_height + (g.offset.y - self.base) * scale,
+                width: (g.src.width() * scale).round() as usize,
+                height: (g.src.height() * scale).round() as usize,
+                pen_x,
+                advance,
+            });
+            pen_x += advance;
+            line_widths[line] = pen_x;
+            prev = Some(c);
+        }
+        TextLayout {
+            size,
+            bounds: ink_bounds(&glyphs),
+            glyphs,
+            metrics,
+            line_widths,
+        }
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// Texels are multiplied by `color`, white glyphs come out in it.
+    fn draw_layout(&self, layout: &TextLayout, pos: VX2, color: Color, renderer: &mut Renderer) {
+        let (clip_x0, clip_y0, clip_x1, clip_y1) = renderer.clip_px();
+        let clr: u32 = color.into();
+        for glyph in &layout.glyphs {
+            let Some(g) = self.glyphs.get(&glyph.c) else {
+                continue;
+            };
+            if glyph.width == 0 || glyph.height == 0 {
+                continue;
+            }
+            let page = &self.pages[g.page];
+            let (page_w, page_h) = (page.size().x as i32, page.size().y as i32);
+            let texels = page.get_buffer().as_slice();
+            let start_x = (pos.x + glyph.x).round() as i32;
+            let start_y = (pos.y + glyph.y).round() as i32;
+            let x0 = start_x.max(clip_x0);
+            let x1 = (start_x + glyph.width as i32).min(clip_x1);
+            let y0 = start_y.max(clip_y0);
+            let y1 = (start_y + glyph.height as i32).min(clip_y1);
+            // nearest texel for the center of every pixel
+            let sx = g.src.width() / glyph.width as f32;
+            let sy = g.src.height() / glyph.height as f32;
+            for y in y0..y1 {
+                let ty = (g.src.min_y + ((y - start_y) as f32 + 0.5) * sy) as i32;
+                if !(0..page_h).contains(&ty) {
+                    continue;
+                }
+                for x in x0..x1 {
+                    let tx = (g.src.min_x + ((x - start_x) as f32 + 0.5) * sx) as i32;
+                    if !(0..page_w).contains(&tx) {
+                        continue;
+                    }
+                    let texel = texels[(ty * page_w + tx) as usize];
+                    if texel >> 24 != 0 {
+                        renderer.blend_pixel(x, y, tint(texel, clr), 1.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Channel by channel product of two ARGB colors.
+fn tint(texel: u32, clr: u32) -> u32 {
+    let mul = |shift: u32| ((texel >> shift & 0xFF) * (clr >> shift & 0xFF) / 255) as u8;
+    argb(mul(16), mul(8), mul(0), mul(24))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::GrapesError,
+        fonts::{BitmapFont, TextFont, layout::TextStyle},
+        renderer::two_d::Renderer,
+        textures::Texture,
+        vx2,
+    };
+
+    const FNT: &str = "info face=\"Pixel Font\" size=8 bold=0\n\
+        common lineHeight=10 base=8 scaleW=16 scaleH=8 pages=1\n\
+        page id=0 file=\"pixel font.png\"\n\
+        chars count=2\n\
+        char id=65 x=0 y=0 width=4 height=8 xoffset=0 yoffset=0 xadvance=5 page=0 chnl=15\n\
+        char id=86 x=4 y=0 width=4 height=6 xoffset=1 yoffset=2 xadvance=5 page=0 chnl=15\n\
+        kernings count=1\n\
+        kerning first=65 second=86 amount=-1\n";
+
+    fn binary() -> Vec<u8> {
+        let block = |id: u8, data: Vec<u8>| {
+            let mut b = vec![id];
+            b.extend((data.len() as u32).to_le_bytes());
+            b.extend(data);
+            b
+        };
+        let mut info = 8i16.to_le_bytes().to_vec();
+        info.extend(b"\0\0\0\0\0\0\0\0\0\0\0\0Pixel Font\0");
+        let mut common = [10u16, 8, 16, 8, 1].map(u16::to_le_bytes).concat();
+        common.extend([0, 0, 0, 0, 0]);
+        let mut chars = Vec::new();
+        for (id, x, w, h, xoff, yoff) in
+            [(65u32, 0u16, 4u16, 8u16, 0i16, 0i16), (86, 4, 4, 6, 1, 2)]
+        {
+            chars.extend(id.to_le_bytes());
+            chars.extend([x, 0, w, h].map(u16::to_le_bytes).concat());
+            chars.extend([xoff, yoff, 5].map(i16::to_le_bytes).concat());
+            chars.extend([0, 15]);
+        }
+        let mut kerning = [65u32, 86].map(u32::to_le_bytes).concat();
+        kerning.extend((-1i16).to_le_bytes());
+
+        let mut fnt = b"BMF\x03".to_vec();
+        fnt.extend(block(1, info));
+        fnt.extend(block(2, common));
+        fnt.extend(block(3, b"pixel font.png\0".to_vec()));
+        fnt.extend(block(4, chars));
+        fnt.extend(block(5, kerning));
+        fnt
+    }
+
+    #[test]
+    fn bmfont_text_and_binary() {
+        let page = || vec![Texture::init(vx2!(16.0, 8.0))];
+        let text = BitmapFont::from_bytes(FNT.as_bytes(), page()).unwrap();
+        let bin = BitmapFont::from_bytes(&binary(), page()).unwrap();
+        for font in [text, bin] {
+            let v = font.glyph('V').unwrap();
+            assert_eq!(
+                (v.src.min_x, v.src.height(), v.offset.x, v.offset.y),
+                (4.0, 6.0, 1.0, 2.0)
+            );
+            // twice the size: kerning and offsets scale along, y is from the baseline
+            let layout = font.layout("AV\nA", 16.0);
+            let pos: Vec<_> = layout
+                .glyphs
+                .iter()
+                .map(|g| (g.c, g.x, g.y, g.height))
+                .collect();
+            assert_eq!(
+                pos,
+                vec![
+                    ('A', 0.0, -16.0, 16),
+                    ('V', 10.0, -12.0, 12),
+                    ('A', 0.0, 4.0, 16)
+                ]
+            );
+            assert_eq!(layout.line_widths, vec![18.0, 10.0]);
+            assert_eq!((layout.metrics.ascent, layout.metrics.descent), (16.0, 4.0));
+            // no '?' to stand in for missing characters
+            assert_eq!(font.layout("AxV", 8.0).glyphs.len(), 2);
+        }
+        assert!(BitmapFont::from_bytes(FNT.as_bytes(), Vec::new()).is_err());
+        assert!(BitmapFont::from_bytes(&binary()[..30], page()).is_err());
+        // page ids past the count, or past a small limit without one
+        let with_id = |id: &str| FNT.replace("page id=0", &format!("page id={id}"));
+        let mut broken = vec![with_id("1")];
+        for id in ["-1", "4000000000", "1e30", "NaN"] {
+            broken.push(with_id(id));
+            broken.push(with_id(id).replace(" pages=1", ""));
+        }
+        for fnt in broken {
+            let font = BitmapFont::from_bytes(fnt.as_bytes(), page());
+            assert!(
+                matches!(font, Err(GrapesError::ParsingResource(_))),
+                "{fnt}"
+            );
+        }
+    }
+
+    #[test]
+    fn grid_font_draws() {
+        // "?" on the left in white, "!" on the right, 2x2 cells
+        let pixels = [0xFFFFFFFF, 0, 0, 0x80FFFFFF, 0, 0xFFFFFFFF, 0, 0];
+        let texture = Texture::from_pixels(4, 2, &pixels).unwrap();
+        let font = BitmapFont::grid(texture, 2, 2, "?!#");
+        assert!(font.has_glyph('!') && !font.has_glyph('#'));
+        assert_eq!(font.layout("a!", 2.0).glyphs[0].c, '?');
+
+        let mut renderer = Renderer::new(8, 4);
+        renderer.draw_text(
+            &font,
+            "?!",
+            vx2!(0.0, 4.0),
+            &TextStyle::new(4.0, 0xFFFF0000u32),
+        );
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 8 + x];
+        // scaled up twice with the nearest texel, tinted red
+        assert_eq!((px(0, 0), px(1, 1), px(2, 0)), (0xFFFF0000, 0xFFFF0000, 0));
+        assert_eq!((px(4, 0), px(6, 0), px(7, 1)), (0, 0x80FF0000, 0x80FF0000));
+    }
+}
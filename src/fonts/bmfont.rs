@@ -0,0 +1,206 @@
+//! AngelCode BMFont descriptors, the text and the binary (version 3) flavour.
+
+use crate::{
+    error::ResultG,
+    linal::vertx2::VX2,
+    objects::utils::Bounds,
+    textures::decode::{Bytes, parse_err},
+    vx2,
+};
+
+use super::bitmap::BitmapGlyph;
+
+const FORMAT: &str = "BMFont";
+/// Most pages a text descriptor can have without a `common pages=` count.
+const MAX_PAGES: usize = 256;
+
+/// Everything in a `.fnt` file, page textures only by file name.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Descriptor {
+    pub size: f32,
+    pub line_height: f32,
+    pub base: f32,
+    pub pages: Vec<String>,
+    pub glyphs: Vec<(char, BitmapGlyph)>,
+    pub kerning: Vec<(char, char, f32)>,
+}
+
+/// Binary files start with "BMF", anything else is read as text.
+pub(crate) fn parse(bytes: &[u8]) -> ResultG<Descriptor> {
+    if bytes.starts_with(b"BMF") {
+        return parse_binary(bytes);
+    }
+    let text = std::str::from_utf8(bytes).map_err(|e| parse_err(FORMAT, &e.to_string()))?;
+    parse_text(text)
+}
+
+fn glyph(x: f32, y: f32, w: f32, h: f32, offset: VX2, advance: f32, page: usize) -> BitmapGlyph {
+    BitmapGlyph {
+        src: Bounds::from_rect(x, y, w, h),
+        page,
+        offset,
+        advance,
+    }
+}
+
+/// Lines of `tag key=value key="quoted value" ...`.
+fn parse_text(text: &str) -> ResultG<Descriptor> {
+    let mut desc = Descriptor::default();
+    let mut page_count = MAX_PAGES;
+    for line in text.lines() {
+        let (tag, fields) = tokenize(line);
+        let get = |key: &str| fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let num = |key: &str| -> ResultG<f32> {
+            let value =
+                get(key).ok_or_else(|| parse_err(FORMAT, &format!("{tag} without {key}")))?;
+            value
+                .parse::<f32>()
+                .map_err(|_| parse_err(FORMAT, &format!("{tag} {key}={value}")))
+        };
+        match tag {
+            "info" => desc.size = num("size")?.abs(),
+            "common" => {
+                desc.line_height = num("lineHeight")?;
+                desc.base = num("base")?;
+                if get("pages").is_some() {
+                    page_count = (num("pages")?.max(0.0) as usize).min(MAX_PAGES);
+                }
+            }
+            "page" => {
+                let id = num("id")?;
+                if !(0.0..page_count as f32).contains(&id) {
+                    return Err(parse_err(FORMAT, &format!("page id={id} out of range")));
+                }
+                let id = id as usize;
+                let file = get("file").unwrap_or_default().to_string();
+                if desc.pages.len() <= id {
+                    desc.pages.resize(id + 1, String::new());
+                }
+                desc.pages[id] = file;
+            }
+            "char" => {
+                let Some(c) = char::from_u32(num("id")? as u32) else {
+                    continue;
+                };
+                let offset = vx2!(num("xoffset")?, num("yoffset")?);
+                let page = get("page").map_or(Ok(0.0), |_| num("page"))? as usize;
+                desc.glyphs.push((
+                    c,
+                    glyph(
+                        num("x")?,
+                        num("y")?,
+                        num("width")?,
+                        num("height")?,
+                        offset,
+                        num("xadvance")?,
+                        page,
+                    ),
+                ));
+            }
+            "kerning" => {
+                let first = char::from_u32(num("first")? as u32);
+                let second = char::from_u32(num("second")? as u32);
+                if let (Some(first), Some(second)) = (first, second) {
+                    desc.kerning.push((first, second, num("amount")?));
+                }
+            }
+            _ => {}
+        }
+    }
+    finish(desc)
+}
+
+/// The tag and the key value pairs of one line, quotes taken off.
+fn tokenize(line: &str) -> (&str, Vec<(&str, &str)>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut fields = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        let Some((key, after)) = rest.split_once('=') else {
+            break;
+        };
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        fields.push((key.trim(), value));
+        rest = next;
+    }
+    (tag, fields)
+}
+
+/// Blocks of id, length and data after "BMF" and the version byte.
+fn parse_binary(bytes: &[u8]) -> ResultG<Descriptor> {
+    let data = Bytes::new(bytes, FORMAT);
+    let version = data.u8(3)?;
+    if version != 3 {
+        return Err(parse_err(FORMAT, &format!("binary version {version}")));
+    }
+    let i16_le = |at: usize| -> ResultG<f32> { Ok(data.u16_le(at)? as i16 as f32) };
+    let mut desc = Descriptor::default();
+    let mut at = 4;
+    while at < bytes.len() {
+        let id = data.u8(at)?;
+        let len = data.u32_le(at + 1)? as usize;
+        let start = at + 5;
+        let block = data.slice(start, len)?;
+        match id {
+            1 => desc.size = i16_le(start)?.abs(),
+            2 => {
+                desc.line_height = data.u16_le(start)? as f32;
+                desc.base = data.u16_le(start + 2)? as f32;
+            }
+            3 => {
+                desc.pages = block
+                    .split(|b| *b == 0)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| String::from_utf8_lossy(name).into_owned())
+                    .collect();
+            }
+            4 => {
+                for entry in (start..start + len).step_by(20) {
+                    let Some(c) = char::from_u32(data.u32_le(entry)?) else {
+                        continue;
+                    };
+                    let u16_at = |o: usize| -> ResultG<f32> { Ok(data.u16_le(entry + o)? as f32) };
+                    let offset = vx2!(i16_le(entry + 12)?, i16_le(entry + 14)?);
+                    desc.glyphs.push((
+                        c,
+                        glyph(
+                            u16_at(4)?,
+                            u16_at(6)?,
+                            u16_at(8)?,
+                            u16_at(10)?,
+                            offset,
+                            i16_le(entry + 16)?,
+                            data.u8(entry + 18)? as usize,
+                        ),
+                    ));
+                }
+            }
+            5 => {
+                for entry in (start..start + len).step_by(10) {
+                    let first = char::from_u32(data.u32_le(entry)?);
+                    let second = char::from_u32(data.u32_le(entry + 4)?);
+                    if let (Some(first), Some(second)) = (first, second) {
+                        desc.kerning.push((first, second, i16_le(entry + 8)?));
+                    }
+                }
+            }
+            _ => {}
+        }
+        at = start + len;
+    }
+    finish(desc)
+}
+
+fn finish(mut desc: Descriptor) -> ResultG<Descriptor> {
+    if desc.line_height <= 0.0 {
+        return Err(parse_err(FORMAT, "missing common line height"));
+    }
+    if desc.size <= 0.0 {
+        desc.size = desc.line_height;
+    }
+    Ok(desc)
+}
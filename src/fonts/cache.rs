@@ -0,0 +1,228 @@
+//! Rasterized glyphs kept around between frames, so text only gets rasterized once.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use crate::textures::atlas::{Atlas, AtlasId};
+
+pub const DEFAULT_GLYPH_CAPACITY: usize = 1024;
+
+pub type GlyphCacheRef = Rc<RefCell<GlyphCache>>;
+
+/// One glyph of one font face at one pixel size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlyphKey {
+    /// identifies the face, fonts loaded from the same file share it
+    pub font: usize,
+    /// bits of the f32 pixel size
+    pub size: u32,
+    /// glyph index in the face
+    pub glyph: u16,
+}
+
+impl GlyphKey {
+    pub fn new(font: usize, size: f32, glyph: u16) -> Self {
+        Self {
+            font,
+            size: size.to_bits(),
+            glyph,
+        }
+    }
+}
+
+/// Coverage bitmap and metrics of a glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    pub width: usize,
+    pub height: usize,
+    /// left edge of the bitmap from the pen position
+    pub xmin: i32,
+    /// bottom edge of the bitmap above the baseline
+    pub ymin: i32,
+    pub advance: f32,
+    /// 0..255 per pixel, row by row
+    pub coverage: Vec<u8>,
+    /// where it is in the cache's atlas, white with the coverage as alpha
+    pub region: Option<AtlasId>,
+}
+
+/// Least recently used glyphs go first once `capacity` is reached. Several fonts can
+/// share one through a [`GlyphCacheRef`].
+///
+/// The atlas only grows: the space of an evicted glyph is reclaimed once the atlas is
+/// full and gets cleared.
+#[derive(Clone)]
+pub struct GlyphCache {
+    capacity: usize,
+    glyphs: HashMap<GlyphKey, (Glyph, u64)>,
+    /// bumped on every lookup, the stamp of a glyph says when it was used last
+    tick: u64,
+    /// the keys by stamp, the least recently used one first
+    recency: BTreeMap<u64, GlyphKey>,
+    atlas: Option<Atlas>,
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_GLYPH_CAPACITY)
+    }
+}
+
+impl GlyphCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            glyphs: HashMap::new(),
+            tick: 0,
+            recency: BTreeMap::new(),
+            atlas: None,
+        }
+    }
+
+    /// Also packs every glyph into `atlas`, fonts drawing through this cache read the
+    /// packed glyphs out of its texture. When it is full it gets cleared and refilled
+    /// with the glyphs used from then on.
+    pub fn with_atlas(mut self, atlas: Atlas) -> Self {
+        self.atlas = Some(atlas);
+        self
+    }
+
+    pub fn into_ref(self) -> GlyphCacheRef {
+        Rc::new(RefCell::new(self))
+    }
+
+    pub fn atlas(&self) -> Option<&Atlas> {
+        self.atlas.as_ref()
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+    pub fn contains(&self, key: &GlyphKey) -> bool {
+        self.glyphs.contains_key(key)
+    }
+    /// Without counting as a use.
+    pub fn get(&self, key: &GlyphKey) -> Option<&Glyph> {
+        self.glyphs.get(key).map(|(glyph, _)| glyph)
+    }
+
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+        self.recency.clear();
+        if let Some(atlas) = self.atlas.as_mut() {
+            atlas.clear();
+        }
+    }
+
+    /// The cached glyph, `rasterize` only runs if it isn't there yet.
+    pub fn get_or_insert<F: FnOnce() -> Glyph>(&mut self, key: GlyphKey, rasterize: F) -> &Glyph {
+        self.tick += 1;
+        let mut glyph = match self.glyphs.remove(&key) {
+            Some((cached, used)) => {
+                self.recency.remove(&used);
+                cached
+            }
+            None => {
+                if self.glyphs.len() >= self.capacity {
+                    self.evict();
+                }
+                rasterize()
+            }
+        };
+        if glyph.region.is_none() {
+            glyph.region = self.pack(&glyph);
+        }
+        self.recency.insert(self.tick, key);
+        &self.glyphs.entry(key).or_insert((glyph, self.tick)).0
+    }
+
+    fn evict(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.glyphs.remove(&key);
+        }
+    }
+
+    fn pack(&mut self, glyph: &Glyph) -> Option<AtlasId> {
+        let atlas = self.atlas.as_mut()?;
+        if glyph.width == 0 || glyph.height == 0 {
+            return None;
+        }
+        let pixels: Vec<u32> = glyph
+            .coverage
+            .iter()
+            .map(|a| (*a as u32) << 24 | 0x00FFFFFF)
+            .collect();
+        let (w, h) = (glyph.width as u32, glyph.height as u32);
+        if let Some(id) = atlas.insert_pixels(w, h, &pixels) {
+            return Some(id);
+        }
+        // full: start over, the glyphs already cached lose their spot
+        atlas.clear();
+        for (cached, _) in self.glyphs.values_mut() {
+            cached.region = None;
+        }
+        atlas.insert_pixels(w, h, &pixels)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fonts::cache::{Glyph, GlyphCache, GlyphKey},
+        textures::atlas::{Atlas, Packer},
+    };
+
+    fn glyph(size: usize) -> Glyph {
+        Glyph {
+            width: size,
+            height: size,
+            xmin: 0,
+            ymin: 0,
+            advance: size as f32,
+            coverage: vec![255; size * size],
+            region: None,
+        }
+    }
+
+    #[test]
+    fn lru_and_atlas() {
+        let mut cache = GlyphCache::new(2).with_atlas(Atlas::new(8, 8, Packer::Skyline).unwrap());
+        let key = |g: u16| GlyphKey::new(1, 12.0, g);
+        let mut rasterized = 0;
+        for g in [1, 2, 1, 3, 1, 2] {
+            cache.get_or_insert(key(g), || {
+                rasterized += 1;
+                glyph(4)
+            });
+        }
+        // 1 stays as it keeps getting used, 2 is dropped for 3 and then 3 for 2
+        assert_eq!(rasterized, 4);
+        assert!(cache.contains(&key(1)) && cache.contains(&key(2)));
+        assert!(!cache.contains(&key(3)));
+        assert_eq!(
+            cache.recency.values().collect::<Vec<_>>(),
+            [&key(1), &key(2)]
+        );
+
+        // 4 glyphs of 4x4 fill the atlas, the fifth starts it over and the ones used
+        // after that get packed again
+        let id = cache.get_or_insert(key(1), || glyph(4)).region.unwrap();
+        let atlas = cache.atlas().unwrap();
+        let region = atlas.region(id).unwrap();
+        assert_eq!((region.width(), region.height()), (4.0, 4.0));
+        let texel = atlas.texture().get_buffer().as_slice()[region.min_x as usize];
+        assert_eq!(texel, 0xFFFFFFFF);
+        cache.get_or_insert(key(5), || glyph(4));
+        assert_eq!(cache.atlas().unwrap().len(), 1);
+        assert!(cache.get_or_insert(key(1), || glyph(4)).region.is_some());
+        assert_eq!(cache.atlas().unwrap().len(), 2);
+    }
+}
@@ -7,12 +7,23 @@ use crate::{
         color::Color,
         modify::{alpha_blend, set_alpha},
     },
+    error::{GrapesError, ResultG},
     linal::vertx2::VX2,
-    textures::Texture,
+    renderer::two_d::Renderer,
+    textures::{Texture, atlas::Atlas},
+};
+
+use super::{
+    cache::{Glyph, GlyphCache, GlyphCacheRef, GlyphKey},
+    layout::{GlyphPosition, LineMetrics, TextLayout, ink_bounds},
+    text::TextFont,
 };
 
 pub struct Font {
     inner: fontdue::Font,
+    /// tried in order for characters `inner` has no glyph for
+    fallbacks: Vec<fontdue::Font>,
+    cache: GlyphCacheRef,
 }
 
 impl Font {
@@ -20,56 +31,431 @@ impl Font {
         let data = std::fs::read(path).unwrap(); //@ERROR
         Self {
             inner: fontdue::Font::from_bytes(data, FontSettings::default()).unwrap(), // @ERROR
+            fallbacks: Vec::new(),
+            cache: GlyphCache::default().into_ref(),
+        }
+    }
+
+    /// TrueType or OpenType font file contents.
+    pub fn from_bytes(data: &[u8]) -> ResultG<Self> {
+        let inner = fontdue::Font::from_bytes(data, FontSettings::default())
+            .map_err(|e| GrapesError::ParsingResource(format!("font: {e}")))?;
+        Ok(Self {
+            inner,
+            fallbacks: Vec::new(),
+            cache: GlyphCache::default().into_ref(),
+        })
+    }
+
+    /// Adds `font`, and its own fallbacks after it, to the fonts tried for characters
+    /// this one doesn't have.
+    pub fn with_fallback(mut self, font: Font) -> Self {
+        self.add_fallback(font);
+        self
+    }
+    pub fn add_fallback(&mut self, font: Font) {
+        self.fallbacks.push(font.inner);
+        self.fallbacks.extend(font.fallbacks);
+    }
+
+    /// Keeps the glyphs in `cache` instead of one of its own, fonts can share one and
+    /// with it one glyph atlas.
+    pub fn with_cache(mut self, cache: GlyphCacheRef) -> Self {
+        self.cache = cache;
+        self
+    }
+    pub fn cache(&self) -> &GlyphCacheRef {
+        &self.cache
+    }
+
+    /// Runs `f` with the glyph of `c` in face `font`, rasterized on first use only, and
+    /// the atlas of the cache if it has one.
+    fn with_glyph<R, F>(&self, font: usize, c: char, fs: f32, f: F) -> R
+    where
+        F: FnOnce(&Glyph, Option<&Atlas>) -> R,
+    {
+        let face = self.face(font);
+        let index = face.lookup_glyph_index(c);
+        let key = GlyphKey::new(face.file_hash(), fs, index);
+        let mut cache = self.cache.borrow_mut();
+        cache.get_or_insert(key, || {
+            let (m, coverage) = face.rasterize_indexed(index, fs);
+            Glyph {
+                width: m.width,
+                height: m.height,
+                xmin: m.xmin,
+                ymin: m.ymin,
+                advance: m.advance_width,
+                coverage,
+                region: None,
+            }
+        });
+        let glyph = cache.get(&key).expect("inserted above");
+        f(glyph, cache.atlas())
+    }
+
+    /// 0 is this font, then the fallbacks in the order they were added.
+    fn face(&self, font: usize) -> &fontdue::Font {
+        match font {
+            0 => &self.inner,
+            _ => &self.fallbacks[font - 1],
         }
     }
 
+    /// In this font or one of the fallbacks.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.inner.has_glyph(c) || self.fallbacks.iter().any(|f| f.has_glyph(c))
+    }
 
-    /// Monospace only at the moment
-    pub fn width(&self, text: &[u8], fs: f32) -> f32 {
-        let rasterized = self.inner.rasterize(text[0] as char, fs);
-        let advance_width = rasterized.0.advance_width.ceil();
-        text.len() as f32 * advance_width
+    /// First font that has a glyph for `c`, this one if none does.
+    fn font_for(&self, c: char) -> usize {
+        if self.inner.has_glyph(c) {
+            return 0;
+        }
+        self.fallbacks
+            .iter()
+            .position(|f| f.has_glyph(c))
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Advance width of the widest line.
+    pub fn width(&self, text: &str, fs: f32) -> f32 {
+        self.layout(text, fs).width()
+    }
+
+    pub fn line_metrics(&self, fs: f32) -> LineMetrics {
+        match self.inner.horizontal_line_metrics(fs) {
+            Some(m) => LineMetrics {
+                ascent: m.ascent,
+                descent: -m.descent,
+                line_gap: m.line_gap,
+                line_height: m.new_line_size,
+            },
+            None => LineMetrics {
+                ascent: fs,
+                descent: 0.0,
+                line_gap: 0.0,
+                line_height: fs,
+            },
+        }
+    }
+
+    /// Positions every glyph with its own advance and the kerning to the one before,
+    /// starting a new line at every `\n`. Characters this font lacks come from the first
+    /// fallback that has them, the line metrics are always this font's.
+    pub fn layout(&self, text: &str, fs: f32) -> TextLayout {
+        let metrics = self.line_metrics(fs);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut line_widths = vec![0.0];
+        let (mut pen_x, mut line) = (0.0, 0);
+        let mut prev: Option<(char, usize)> = None;
+
+        for (index, c) in text.char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_widths.push(0.0);
+                (pen_x, prev) = (0.0, None);
+                continue;
+            }
+            if c.is_control() {
+                continue;
+            }
+            let font = self.font_for(c);
+            let face = self.face(font);
+            // kerning pairs only exist within one font
+            if let Some((p, _)) = prev.filter(|(_, f)| *f == font) {
+                pen_x += face.horizontal_kern(p, c, fs).unwrap_or(0.0);
+            }
+            let (xmin, ymin, width, height, advance) =
+                self.with_glyph(font, c, fs, |g, _| (g.xmin, g.ymin, g.width, g.height, g.advance));
+            let baseline = line as f32 * metrics.line_height;
+            let glyph = GlyphPosition {
+                c,
+                font,
+                index,
+                line,
+                x: pen_x + xmin as f32,
+                y: baseline - (ymin + height as i32) as f32,
+                width,
+                height,
+                pen_x,
+                advance,
+            };
+            glyphs.push(glyph);
+            pen_x += advance;
+            line_widths[line] = pen_x;
+            prev = Some((c, font));
+        }
+        TextLayout {
+            size: fs,
+            bounds: ink_bounds(&glyphs),
+            glyphs,
+            metrics,
+            line_widths,
+        }
     }
 
     pub fn render_into_texture(
         &self,
-        text: &[u8],
+        text: &str,
         pos: VX2,
         fs: f32,
         color: Color,
         texture: &mut Texture,
     ) {
-        if text.len() == 0 {
-            return;
-        }
-        let rasterized = self.inner.rasterize(text[0] as char, fs);
-
-        let advance_width = rasterized.0.advance_width.ceil();
-        let base_y = pos.y;
-        let mut line_x = pos.x;
-
-        for c in text {
-            let (metrics, data) = self.inner.rasterize((*c) as char, fs);
-            let start_x = line_x as i32 + metrics.xmin;
-            let bottom_y = base_y as i32 - metrics.ymin;
-            let hi_y = bottom_y - metrics.height as i32;
-            let mut data_idx = 0;
-            let frame = texture.get_buffer_mut();
-            let stride = frame.width();
-            for y in hi_y..bottom_y {
-                unsafe {
-                    if let Some(scan_line) = frame.get(y * stride as i32 + start_x) {
-                        for x in 0..metrics.width {
-                            let pix = *scan_line.add(x);
-                            let coverage = data[data_idx];
-                            data_idx += 1;
-                            let blend = alpha_blend(set_alpha(color.into(), coverage), pix);
-                            scan_line.add(x).write(blend);
-                        }
+        let frame = texture.get_buffer_mut();
+        let clip = (0, 0, frame.width(), frame.height());
+        let clr: u32 = color.into();
+        let layout = self.layout(text, fs);
+        self.rasterize(&layout, pos, clip, |x, y, coverage| unsafe {
+            let pix = frame.offset_xy(x, y);
+            pix.write(alpha_blend(set_alpha(clr, coverage), *pix));
+        });
+    }
+
+    /// Same as `render_into_texture`, but draws with the renderer: its transform, clip and
+    /// blend mode apply, so this also works while the renderer draws into a texture.
+    pub fn render(&self, text: &str, pos: VX2, fs: f32, color: Color, renderer: &mut Renderer) {
+        let pos = renderer.to_screen(&pos);
+        let fs = renderer.to_screen_scale(fs);
+        let clip = renderer.clip_px();
+        let clr: u32 = color.into();
+        let layout = self.layout(text, fs);
+        self.rasterize(&layout, pos, clip, |x, y, coverage| {
+            renderer.blend_pixel(x, y, clr, coverage as f32 / 255.0);
+        });
+    }
+
+    /// Calls `plot` with the coverage of every glyph pixel inside `clip` (x0, y0, x1, y1),
+    /// `pos` is the origin of the layout.
+    fn rasterize<F: FnMut(i32, i32, u8)>(
+        &self,
+        layout: &TextLayout,
+        pos: VX2,
+        (clip_x0, clip_y0, clip_x1, clip_y1): (i32, i32, i32, i32),
+        mut plot: F,
+    ) {
+        for glyph in &layout.glyphs {
+            if glyph.width == 0 || glyph.height == 0 {
+                continue;
+            }
+            let start_x = (pos.x + glyph.x).round() as i32;
+            let hi_y = (pos.y + glyph.y).round() as i32;
+            // clip the glyph a row at a time
+            let x0 = start_x.max(clip_x0);
+            let x1 = (start_x + glyph.width as i32).min(clip_x1);
+            let y0 = hi_y.max(clip_y0);
+            let y1 = (hi_y + glyph.height as i32).min(clip_y1);
+            self.with_glyph(glyph.font, glyph.c, layout.size, |cached, atlas| {
+                // packed glyphs come out of the atlas, the coverage is the texel alpha
+                let packed = cached.region.zip(atlas).and_then(|(id, atlas)| {
+                    let texture = atlas.texture().get_buffer();
+                    Some((atlas.region(id)?, texture.as_slice(), texture.width() as usize))
+                });
+                for y in y0..y1 {
+                    let row = (y - hi_y) as usize;
+                    for x in x0..x1 {
+                        let col = (x - start_x) as usize;
+                        let coverage = match packed {
+                            Some((region, texels, stride)) => {
+                                let at = (region.min_y as usize + row) * stride;
+                                (texels[at + region.min_x as usize + col] >> 24) as u8
+                            }
+                            None => cached.coverage[row * glyph.width + col],
+                        };
+                        plot(x, y, coverage);
                     }
                 }
+            });
+        }
+    }
+}
+
+impl TextFont for Font {
+    fn layout(&self, text: &str, size: f32) -> TextLayout {
+        Font::layout(self, text, size)
+    }
+    fn has_glyph(&self, c: char) -> bool {
+        Font::has_glyph(self, c)
+    }
+    fn draw_layout(&self, layout: &TextLayout, pos: VX2, color: Color, renderer: &mut Renderer) {
+        let clip = renderer.clip_px();
+        let clr: u32 = color.into();
+        self.rasterize(layout, pos, clip, |x, y, coverage| {
+            renderer.blend_pixel(x, y, clr, coverage as f32 / 255.0);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fonts::{Font, cache::GlyphCache, layout::TextStyle},
+        renderer::two_d::Renderer,
+        textures::atlas::{Atlas, Packer},
+        vx2,
+    };
+
+    /// TrueType font with 1000 units per em, an ascender of 800, a descender of -200 and a
+    /// line gap of 100. Every glyph but ' ' is a box from 100 to its advance - 100 wide and
+    /// from 0 to 700 high.
+    fn ttf(glyphs: &[(char, u16)], kerning: &[(char, char, i16)]) -> Vec<u8> {
+        let be16 = |v: i32| (v as u16).to_be_bytes();
+        let count = glyphs.len() + 1;
+        let glyph_id = |c: char| 1 + glyphs.iter().position(|(g, _)| *g == c).unwrap() as i32;
+
+        let mut head = [0x0001_0000u32, 0, 0, 0x5F0F_3CF5].map(u32::to_be_bytes).concat();
+        head.extend([be16(0), be16(1000)].concat());
+        head.extend([0; 16]);
+        head.extend([0, 0, 1000, 1000].map(be16).concat());
+        // mac style, lowest size, direction, long offsets, glyph data format
+        head.extend([0, 8, 2, 1, 0].map(be16).concat());
+
+        let mut hhea = 0x0001_0000u32.to_be_bytes().to_vec();
+        hhea.extend([800, -200, 100, 1000, 0, 0, 1000, 1, 0, 0].map(be16).concat());
+        hhea.extend([0; 10]);
+        hhea.extend(be16(count as i32));
+
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend(be16(count as i32));
+
+        let mut hmtx = [be16(500), be16(0)].concat();
+        let (mut glyf, mut loca) = (Vec::new(), vec![0u32, 0]);
+        for (c, advance) in glyphs {
+            let advance = *advance as i32;
+            hmtx.extend([be16(advance), be16(100)].concat());
+            if *c != ' ' {
+                let (x0, x1) = (100, advance - 100);
+                glyf.extend([1, x0, 0, x1, 700, 3, 0].map(be16).concat());
+                glyf.extend([1u8; 4]);
+                glyf.extend([x0, 0, x1 - x0, 0, 0, 700, 0, -700].map(be16).concat());
             }
-            line_x += advance_width;
+            loca.push(glyf.len() as u32);
         }
+        let loca = loca.iter().flat_map(|o| o.to_be_bytes()).collect::<Vec<_>>();
+
+        // one format 12 subtable, a group per character sorted by code point
+        let mut cmap = [0, 1, 0, 4].map(be16).concat();
+        cmap.extend(12u32.to_be_bytes());
+        cmap.extend([12, 0].map(be16).concat());
+        let mut chars: Vec<char> = glyphs.iter().map(|(c, _)| *c).collect();
+        chars.sort();
+        let groups = chars.iter().map(|c| [*c as u32, *c as u32, glyph_id(*c) as u32]);
+        let groups: Vec<u8> = groups.flatten().flat_map(u32::to_be_bytes).collect();
+        let sizes = [16 + groups.len() as u32, 0, glyphs.len() as u32];
+        cmap.extend(sizes.map(u32::to_be_bytes).concat());
+        cmap.extend(groups);
+
+        let mut kern = [0, 1, 0, 14 + 6 * kerning.len() as i32, 1].map(be16).concat();
+        kern.extend([kerning.len() as i32, 0, 0, 0].map(be16).concat());
+        for (left, right, value) in kerning {
+            kern.extend([glyph_id(*left), glyph_id(*right), *value as i32].map(be16).concat());
+        }
+
+        let mut tables = vec![
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"kern", kern),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        let mut font = 0x0001_0000u32.to_be_bytes().to_vec();
+        font.extend([tables.len() as i32, 0, 0, 0].map(be16).concat());
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, data) in &mut tables {
+            data.resize(data.len().next_multiple_of(4), 0);
+            font.extend(tag.iter());
+            font.extend([0, offset as u32, data.len() as u32].map(u32::to_be_bytes).concat());
+            offset += data.len();
+        }
+        for (_, data) in tables {
+            font.extend(data);
+        }
+        font
+    }
+
+    #[test]
+    fn advances_kerning_and_metrics() {
+        let data = ttf(&[('A', 600), ('V', 600), ('i', 300), (' ', 250)], &[('A', 'V', -100)]);
+        let font = Font::from_bytes(&data).unwrap();
+        let m = font.line_metrics(10.0);
+        assert_eq!((m.ascent, m.descent, m.line_gap, m.line_height), (8.0, 2.0, 1.0, 11.0));
+
+        let layout = font.layout("AV i\nVA", 10.0);
+        let pens: Vec<_> = layout.glyphs.iter().map(|g| (g.c, g.pen_x, g.advance)).collect();
+        // A V is a kerning pair, V A isn't
+        assert_eq!(
+            pens,
+            vec![
+                ('A', 0.0, 6.0),
+                ('V', 5.0, 6.0),
+                (' ', 11.0, 2.5),
+                ('i', 13.5, 3.0),
+                ('V', 0.0, 6.0),
+                ('A', 6.0, 6.0)
+            ]
+        );
+        assert_eq!(layout.line_widths, vec![16.5, 12.0]);
+        assert_eq!(layout.height(), 8.0 + 2.0 + 11.0);
+
+        // boxes 7 high sitting on the baselines, 1 in from the pen on both sides
+        let v = &layout.glyphs[4];
+        assert_eq!((v.x, v.y, v.width, v.height), (1.0, 11.0 - 7.0, 4, 7));
+        assert_eq!(layout.bounds.min_y, -7.0);
+        assert_eq!(layout.bounds.max_y, 11.0);
+        // the right edge of the i
+        assert_eq!(layout.bounds.max_x, 13.5 + 2.0);
+    }
+
+    #[test]
+    fn fallback_and_byte_indices() {
+        let primary = ttf(&[('A', 600), ('V', 600), ('é', 500)], &[('A', 'V', -100)]);
+        // has its own V, the primary's goes first
+        let fallback = ttf(&[('€', 700), ('V', 400)], &[]);
+        let font = Font::from_bytes(&primary)
+            .unwrap()
+            .with_fallback(Font::from_bytes(&fallback).unwrap());
+        assert!(font.has_glyph('€'));
+        assert!(!font.has_glyph('ß'));
+
+        let text = "é€AVß";
+        let layout = font.layout(text, 10.0);
+        let placed = layout.glyphs.iter().map(|g| (g.c, g.index, g.font, g.pen_x));
+        assert_eq!(
+            placed.collect::<Vec<_>>(),
+            vec![
+                ('é', 0, 0, 0.0),
+                ('€', 2, 1, 5.0),
+                ('A', 5, 0, 12.0),
+                ('V', 6, 0, 17.0),
+                // in no font, the primary's missing glyph
+                ('ß', 7, 0, 23.0)
+            ]
+        );
+        assert!(layout.glyphs.iter().all(|g| text[g.index..].starts_with(g.c)));
+    }
+
+    #[test]
+    fn draws_out_of_the_atlas() {
+        let data = ttf(&[('A', 600), ('V', 600), (' ', 250)], &[('A', 'V', -100)]);
+        let atlas = Atlas::new(32, 32, Packer::Skyline).unwrap();
+        let cache = GlyphCache::new(16).with_atlas(atlas).into_ref();
+        let packed = Font::from_bytes(&data).unwrap().with_cache(cache.clone());
+        let plain = Font::from_bytes(&data).unwrap();
+
+        let draw = |font: &Font| {
+            let mut renderer = Renderer::new(32, 16);
+            let style = TextStyle::new(10.0, 0xFFFFFFFFu32);
+            renderer.draw_text(font, "AV A", vx2!(1.0, 12.0), &style);
+            renderer.buffer().as_slice().to_vec()
+        };
+        let pixels = draw(&packed);
+        assert!(pixels.iter().any(|p| *p != 0));
+        assert_eq!(pixels, draw(&plain));
+        // A and V, the space has no pixels
+        assert_eq!(cache.borrow().atlas().unwrap().len(), 2);
     }
 }
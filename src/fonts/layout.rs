@@ -0,0 +1,363 @@
+use crate::{colors::color::Color, constants::DEFAULT_FOREGROUND, objects::utils::Bounds};
+
+/// Where the x of the text position is on every line.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where the y of the text position is.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum VAlign {
+    /// top of the first line, its ascent
+    Top,
+    /// baseline of the first line
+    #[default]
+    Baseline,
+    /// bottom of the last line, its descent
+    Bottom,
+}
+
+/// What happens to lines wider than the max width.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// break them between words, between characters for words too long for a line
+    #[default]
+    Wrap,
+    /// cut them short and end them with an ellipsis
+    Ellipsis,
+}
+
+/// How [`crate::renderer::two_d::Renderer::draw_text`] lays out and colors text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    /// pixels
+    pub size: f32,
+    pub color: Color,
+    pub align: Align,
+    pub valign: VAlign,
+    /// no limit if None
+    pub max_width: Option<f32>,
+    pub overflow: Overflow,
+}
+
+impl TextStyle {
+    pub fn new<C: Into<Color>>(size: f32, color: C) -> Self {
+        Self {
+            size,
+            color: color.into(),
+            align: Align::Left,
+            valign: VAlign::Baseline,
+            max_width: None,
+            overflow: Overflow::Wrap,
+        }
+    }
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+    pub fn with_valign(mut self, valign: VAlign) -> Self {
+        self.valign = valign;
+        self
+    }
+    pub fn with_max_width(mut self, max_width: f32, overflow: Overflow) -> Self {
+        self.max_width = Some(max_width);
+        self.overflow = overflow;
+        self
+    }
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::new(16.0, Color::from_argb(DEFAULT_FOREGROUND))
+    }
+}
+
+/// Vertical metrics of a font at one size, in pixels. y grows downwards, so both
+/// `ascent` (above the baseline) and `descent` (below it) are positive.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+    /// baseline to baseline
+    pub line_height: f32,
+}
+
+/// Where one glyph goes, relative to the start of the first baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPosition {
+    pub c: char,
+    /// 0 for the font itself, 1 and up for its fallbacks
+    pub font: usize,
+    /// byte offset of `c` in the text
+    pub index: usize,
+    pub line: usize,
+    /// top left of the glyph bitmap
+    pub x: f32,
+    pub y: f32,
+    pub width: usize,
+    pub height: usize,
+    /// pen position before the glyph, kerning included
+    pub pen_x: f32,
+    pub advance: f32,
+}
+
+/// Text laid out in lines, split at `\n`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    pub size: f32,
+    pub glyphs: Vec<GlyphPosition>,
+    pub metrics: LineMetrics,
+    /// advance width of every line
+    pub line_widths: Vec<f32>,
+    /// of the pixels the glyphs cover, empty without any
+    pub bounds: Bounds,
+}
+
+impl TextLayout {
+    pub fn lines(&self) -> usize {
+        self.line_widths.len()
+    }
+    /// Of the widest line.
+    pub fn width(&self) -> f32 {
+        self.line_widths.iter().fold(0.0, |w, l| w.max(*l))
+    }
+    /// From the ascent of the first line to the descent of the last.
+    pub fn height(&self) -> f32 {
+        let m = &self.metrics;
+        m.ascent + m.descent + self.lines().saturating_sub(1) as f32 * m.line_height
+    }
+    /// y of the baseline of `line`.
+    pub fn baseline(&self, line: usize) -> f32 {
+        line as f32 * self.metrics.line_height
+    }
+
+    /// Breaks lines wider than `max_width` after the last whitespace that keeps them
+    /// inside, between characters if a word is too long for a line of its own.
+    /// Whitespace at the end of a line doesn't count towards its width.
+    pub fn wrap(&self, max_width: f32) -> TextLayout {
+        // line and pen offset every glyph ends up with
+        let mut placed: Vec<(usize, f32)> = Vec::with_capacity(self.glyphs.len());
+        let (mut line, mut src_line, mut line_start, mut shift) = (0, 0, 0, 0.0);
+        for (i, g) in self.glyphs.iter().enumerate() {
+            if g.line > src_line {
+                line += g.line - src_line;
+                (src_line, line_start, shift) = (g.line, i, g.pen_x);
+            }
+            if g.pen_x + g.advance - shift > max_width && !g.c.is_whitespace() && i > line_start {
+                let brk = (line_start + 1..i)
+                    .rev()
+                    .find(|&b| {
+                        self.glyphs[b - 1].c.is_whitespace() && !self.glyphs[b].c.is_whitespace()
+                    })
+                    .unwrap_or(i);
+                line += 1;
+                (line_start, shift) = (brk, self.glyphs[brk].pen_x);
+                for p in &mut placed[brk..] {
+                    *p = (line, shift);
+                }
+            }
+            placed.push((line, shift));
+        }
+        line += self.lines().saturating_sub(1) - src_line;
+
+        let mut line_widths = vec![0.0; line + 1];
+        let glyphs: Vec<GlyphPosition> = self
+            .glyphs
+            .iter()
+            .zip(placed)
+            .map(|(g, (line, shift))| {
+                if !g.c.is_whitespace() {
+                    let w = &mut line_widths[line];
+                    *w = f32::max(*w, g.pen_x + g.advance - shift);
+                }
+                let dy = self.baseline(line) - self.baseline(g.line);
+                GlyphPosition {
+                    line,
+                    x: g.x - shift,
+                    y: g.y + dy,
+                    pen_x: g.pen_x - shift,
+                    ..*g
+                }
+            })
+            .collect();
+        TextLayout {
+            size: self.size,
+            bounds: ink_bounds(&glyphs),
+            glyphs,
+            metrics: self.metrics,
+            line_widths,
+        }
+    }
+
+    /// Cuts lines wider than `max_width` short and puts `ellipsis`, laid out in the same
+    /// font and size, at their end.
+    pub fn ellipsize(&self, max_width: f32, ellipsis: &TextLayout) -> TextLayout {
+        let room = max_width - ellipsis.width();
+        let mut line_widths = self.line_widths.clone();
+        let mut glyphs = Vec::with_capacity(self.glyphs.len());
+        for (line, width) in line_widths.iter_mut().enumerate() {
+            let on_line = self.glyphs.iter().filter(|g| g.line == line);
+            if *width <= max_width {
+                glyphs.extend(on_line);
+                continue;
+            }
+            let kept: Vec<&GlyphPosition> = on_line
+                .take_while(|g| g.pen_x + g.advance <= room)
+                .collect();
+            // no whitespace right before the ellipsis
+            let end = kept
+                .iter()
+                .rposition(|g| !g.c.is_whitespace())
+                .map_or(0, |i| i + 1);
+            let pen = kept
+                .get(end.wrapping_sub(1))
+                .map_or(0.0, |g| g.pen_x + g.advance);
+            let index = self
+                .glyphs
+                .iter()
+                .filter(|g| g.line == line)
+                .nth(end)
+                .map_or(0, |g| g.index);
+            glyphs.extend(kept[..end].iter().copied());
+            let dy = self.baseline(line);
+            glyphs.extend(ellipsis.glyphs.iter().map(|e| GlyphPosition {
+                index,
+                line,
+                x: e.x + pen,
+                y: e.y + dy,
+                pen_x: e.pen_x + pen,
+                ..*e
+            }));
+            *width = pen + ellipsis.width();
+        }
+        TextLayout {
+            size: self.size,
+            bounds: ink_bounds(&glyphs),
+            glyphs,
+            metrics: self.metrics,
+            line_widths,
+        }
+    }
+
+    /// Moves everything so the origin ends up where `align` and `valign` say.
+    pub fn align(&mut self, align: Align, valign: VAlign) {
+        let dy = match valign {
+            VAlign::Top => self.metrics.ascent,
+            VAlign::Baseline => 0.0,
+            VAlign::Bottom => {
+                -(self.baseline(self.lines().saturating_sub(1)) + self.metrics.descent)
+            }
+        };
+        let factor = match align {
+            Align::Left => 0.0,
+            Align::Center => 0.5,
+            Align::Right => 1.0,
+        };
+        for g in &mut self.glyphs {
+            let dx = -self.line_widths[g.line] * factor;
+            g.x += dx;
+            g.pen_x += dx;
+            g.y += dy;
+        }
+        self.bounds = ink_bounds(&self.glyphs);
+    }
+}
+
+/// Box around the bitmaps of all the glyphs, empty without any.
+pub(crate) fn ink_bounds(glyphs: &[GlyphPosition]) -> Bounds {
+    glyphs
+        .iter()
+        .filter(|g| g.width > 0 && g.height > 0)
+        .map(|g| Bounds::from_rect(g.x, g.y, g.width as f32, g.height as f32))
+        .reduce(|a, b| Bounds {
+            min_x: a.min_x.min(b.min_x),
+            max_x: a.max_x.max(b.max_x),
+            min_y: a.min_y.min(b.min_y),
+            max_y: a.max_y.max(b.max_y),
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fonts::layout::{Align, GlyphPosition, LineMetrics, TextLayout, VAlign, ink_bounds};
+
+    /// Every glyph 10 wide with an 8x10 bitmap, lines 20 apart.
+    fn mono(text: &str) -> TextLayout {
+        let (mut line, mut pen_x, mut line_widths) = (0, 0.0, vec![0.0]);
+        let mut glyphs = Vec::new();
+        for (index, c) in text.char_indices() {
+            if c == '\n' {
+                (line, pen_x) = (line + 1, 0.0);
+                line_widths.push(0.0);
+                continue;
+            }
+            let size = if c == ' ' { 0 } else { 8 };
+            glyphs.push(GlyphPosition {
+                c,
+                font: 0,
+                index,
+                line,
+                x: pen_x + 1.0,
+                y: line as f32 * 20.0 - 10.0,
+                width: size,
+                height: size,
+                pen_x,
+                advance: 10.0,
+            });
+            pen_x += 10.0;
+            line_widths[line] = pen_x;
+        }
+        TextLayout {
+            size: 10.0,
+            bounds: ink_bounds(&glyphs),
+            glyphs,
+            metrics: LineMetrics {
+                ascent: 12.0,
+                descent: 4.0,
+                line_gap: 4.0,
+                line_height: 20.0,
+            },
+            line_widths,
+        }
+    }
+
+    fn lines(layout: &TextLayout) -> Vec<String> {
+        (0..layout.lines())
+            .map(|l| {
+                let on_line = layout.glyphs.iter().filter(|g| g.line == l);
+                on_line.map(|g| g.c).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wrap_ellipsis_and_align() {
+        let wrapped = mono("one two three\n\nabcdefgh").wrap(50.0);
+        assert_eq!(
+            lines(&wrapped),
+            vec!["one ", "two ", "three", "", "abcde", "fgh"]
+        );
+        assert_eq!(wrapped.line_widths, vec![30.0, 30.0, 50.0, 0.0, 50.0, 30.0]);
+        let t = wrapped.glyphs.iter().find(|g| g.c == 't').unwrap();
+        assert_eq!((t.x, t.y, t.line), (1.0, 10.0, 1));
+
+        let cut = mono("short\nfar too long").ellipsize(60.0, &mono("~"));
+        assert_eq!(lines(&cut), vec!["short", "far t~"]);
+        assert_eq!(cut.line_widths, vec![50.0, 60.0]);
+        let tilde = cut.glyphs.last().unwrap();
+        assert_eq!((tilde.x, tilde.y, tilde.index), (51.0, 10.0, 11));
+
+        let mut centered = mono("ab\nabcd");
+        centered.align(Align::Center, VAlign::Bottom);
+        let first = &centered.glyphs[0];
+        // bottom of the second line's descent is at y 0
+        assert_eq!((first.x, first.y), (-9.0, -34.0));
+        assert_eq!(centered.bounds.min_x, -19.0);
+    }
+}
@@ -1,2 +1,9 @@
+pub mod bitmap;
+mod bmfont;
+pub mod cache;
 mod fonts;
+pub mod layout;
+pub mod text;
+pub use bitmap::BitmapFont;
 pub use fonts::Font;
+pub use text::TextFont;
@@ -0,0 +1,49 @@
+use crate::{colors::color::Color, linal::vertx2::VX2, renderer::two_d::Renderer};
+
+use super::layout::{Overflow, TextLayout, TextStyle};
+
+/// What [`crate::renderer::two_d::Renderer::draw_text`] needs from a font, outline and
+/// bitmap fonts both lay out and draw through this.
+pub trait TextFont {
+    /// Glyph positions of `text` at `size` pixels, lines split at `\n`.
+    fn layout(&self, text: &str, size: f32) -> TextLayout;
+
+    fn has_glyph(&self, c: char) -> bool;
+
+    /// Draws a layout made by this font with its origin at `pos`, both in screen pixels.
+    fn draw_layout(&self, layout: &TextLayout, pos: VX2, color: Color, renderer: &mut Renderer);
+
+    /// Advance width of the widest line.
+    fn width(&self, text: &str, size: f32) -> f32 {
+        self.layout(text, size).width()
+    }
+
+    /// [`TextFont::layout`] with the wrapping or ellipsis and the alignment of `style` applied.
+    fn layout_styled(&self, text: &str, style: &TextStyle) -> TextLayout {
+        let mut layout = self.layout(text, style.size);
+        if let Some(max_width) = style.max_width {
+            layout = match style.overflow {
+                Overflow::Wrap => layout.wrap(max_width),
+                Overflow::Ellipsis => {
+                    let ellipsis = if self.has_glyph('…') { "…" } else { "..." };
+                    layout.ellipsize(max_width, &self.layout(ellipsis, style.size))
+                }
+            };
+        }
+        layout.align(style.align, style.valign);
+        layout
+    }
+
+    /// What [`Renderer::draw_text`] does. The size and max width get scaled along with the
+    /// transform and camera, rotation doesn't apply.
+    fn draw(&self, text: &str, pos: VX2, style: &TextStyle, renderer: &mut Renderer) {
+        let pos = renderer.to_screen(&pos);
+        let style = TextStyle {
+            size: renderer.to_screen_scale(style.size),
+            max_width: style.max_width.map(|w| renderer.to_screen_scale(w)),
+            ..*style
+        };
+        let layout = self.layout_styled(text, &style);
+        self.draw_layout(&layout, pos, style.color, renderer);
+    }
+}
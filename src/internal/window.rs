@@ -14,9 +14,11 @@ use x11_dl::xlib::{
 };
 
 use crate::{
+    backend::{Backend, blit_scaled},
     error::{ErrorX, ResultX},
     events::keyboard::K,
     internal::{dsp::Dsp, event::EventProcessResult, keys::Keys, rate::Fps},
+    renderer::two_d::ResizePolicy,
 };
 
 #[allow(unused)]
@@ -44,6 +46,7 @@ pub struct Window {
     active: bool,
     fps: Fps,
     should_close: bool,
+    resized: Option<(u32, u32)>,
     key_state: Keys,
 }
 
@@ -217,6 +220,7 @@ impl Window {
                 buttons: [0, 0, 0],
                 active: false,
                 should_close: false,
+                resized: None,
                 fps: Fps::new(),
                 key_state: Keys::new(),
             })
@@ -283,45 +287,22 @@ impl Window {
         }
     }
 
-    pub fn set_title(&mut self, title: &str) {
-        match CString::new(title) {
-            Err(_) => {
-                println!("Unable to convert {} to c_string", title);
-            }
-            Ok(t) => {
-                if let Err(err) = Self::set_title_raw(&mut self.dsp, self.handle, &t) {
-                    println!("{err}");
-                }
-            }
-        }
-    }
-
-    pub fn update_with_buffer_stride(
+    fn raw_blit_buffer(
         &mut self,
         buffer: &[u32],
         buf_width: usize,
         buf_height: usize,
         buf_stride: usize,
-    ) -> ResultX<()> {
-        self.raw_blit_buffer(buffer, buf_width, buf_height, buf_stride);
-        self.update();
-        Ok(())
-    }
-
-    pub fn size(&self) -> (usize, usize) {
-        (self.width as usize, self.height as usize)
-    }
-
-    fn raw_blit_buffer(
-        &mut self,
-        buffer: &[u32],
-        _buf_width: usize,
-        _buf_height: usize,
-        _buf_stride: usize,
+        policy: ResizePolicy,
     ) {
-        for (i, p) in self.buffer.iter_mut().enumerate() {
-            *p = buffer[i];
-        }
+        blit_scaled(
+            buffer,
+            (buf_width, buf_height),
+            buf_stride,
+            &mut self.buffer,
+            (self.width as usize, self.height as usize),
+            policy,
+        );
 
         unsafe {
             (self.dsp.lib.XPutImage)(
@@ -340,12 +321,29 @@ impl Window {
         }
     }
 
-    pub fn set_target_fps(&mut self, fps: usize) {
-        self.fps.set_target_fps(fps);
-    }
-
-    pub fn get_fps(&self) -> usize {
-        self.fps.get_target_fps()
+    fn raw_resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 || (width, height) == (self.width, self.height) {
+            return;
+        }
+        self.free_image();
+        match Self::alloc_image(&self.dsp, width as usize, height as usize, &mut self.buffer) {
+            Some(ximg) => {
+                self.ximage = ximg;
+                self.width = width;
+                self.height = height;
+                self.resized = Some((width, height));
+            }
+            None => {
+                // keep the old size, its image can always be rebuilt
+                self.ximage = Self::alloc_image(
+                    &self.dsp,
+                    self.width as usize,
+                    self.height as usize,
+                    &mut self.buffer,
+                )
+                .expect("XImage of previous size");
+            }
+        }
     }
 
     fn raw_process_events(&mut self) {
@@ -387,7 +385,9 @@ impl Window {
                 }
                 xlib::ButtonPress => {}
                 xlib::ButtonRelease => {}
-                xlib::ConfigureNotify => {}
+                xlib::ConfigureNotify => {
+                    self.raw_resize(event.configure.width as u32, event.configure.height as u32);
+                }
                 xlib::FocusIn => {
                     self.active = true;
                 }
@@ -400,10 +400,6 @@ impl Window {
         }
     }
 
-    pub fn should_close(&self) -> bool {
-        self.should_close
-    }
-
     pub fn update(&mut self) {
         self.key_state.update();
         self.fps.update();
@@ -431,16 +427,6 @@ impl Window {
         }
     }
 
-    pub fn key_pressed(&self, k: K) -> bool {
-        self.key_state.is_pressed(k)
-    }
-    pub fn key_down(&self, k: K) -> bool {
-        self.key_state.is_down(k)
-    }
-    pub fn key_released(&self, k: K) -> bool {
-        self.key_state.is_released(k)
-    }
-
     fn raw_get_mouse_pos(&mut self) {
         let mut root: xlib::Window = 0;
         let mut root_x: i32 = 0;
@@ -502,20 +488,74 @@ impl Window {
             self.scroll_y += scroll.1 as f32 * 0.1;
         }
     }
+}
+
+impl Backend for Window {
+    fn set_title(&mut self, title: &str) {
+        match CString::new(title) {
+            Err(_) => {
+                println!("Unable to convert {} to c_string", title);
+            }
+            Ok(t) => {
+                if let Err(err) = Self::set_title_raw(&mut self.dsp, self.handle, &t) {
+                    println!("{err}");
+                }
+            }
+        }
+    }
+
+    fn update_with_buffer_stride(
+        &mut self,
+        buffer: &[u32],
+        buf_width: usize,
+        buf_height: usize,
+        buf_stride: usize,
+        policy: ResizePolicy,
+    ) -> ResultX<()> {
+        self.raw_blit_buffer(buffer, buf_width, buf_height, buf_stride, policy);
+        self.update();
+        Ok(())
+    }
+
+    fn take_resize(&mut self) -> Option<(u32, u32)> {
+        self.resized.take()
+    }
+
+    fn set_target_fps(&mut self, fps: usize) {
+        self.fps.set_target_fps(fps);
+    }
+
+    fn get_fps(&self) -> usize {
+        self.fps.get_target_fps()
+    }
+
+    fn should_close(&self) -> bool {
+        self.should_close
+    }
+
+    fn key_pressed(&self, k: K) -> bool {
+        self.key_state.is_pressed(k)
+    }
+    fn key_down(&self, k: K) -> bool {
+        self.key_state.is_down(k)
+    }
+    fn key_released(&self, k: K) -> bool {
+        self.key_state.is_released(k)
+    }
 
-    pub fn get_size(&self) -> (u32, u32) {
+    fn get_size(&self) -> (u32, u32) {
         (self.width, self.height)
     }
 
-    pub fn get_mouse_position(&self) -> Option<(f32, f32)> {
+    fn get_mouse_position(&self) -> Option<(f32, f32)> {
         Some((self.mouse_x, self.mouse_y))
     }
 
-    pub fn get_screen_size(&self) -> (u32, u32) {
+    fn get_screen_size(&self) -> (u32, u32) {
         self.dsp.get_size()
     }
 
-    pub fn button_is_down(&self, b: usize) -> bool {
+    fn button_is_down(&self, b: usize) -> bool {
         self.buttons[b] > 0
     }
 }
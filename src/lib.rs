@@ -1,3 +1,4 @@
+pub mod backend;
 pub mod buffer;
 pub mod colors;
 pub mod constants;
@@ -0,0 +1,143 @@
+use std::ops::{Mul, MulAssign};
+
+use crate::{linal::vertx2::VX2, vx2};
+
+/// 2d affine transform as a row major 3x3 matrix. The last row is always `[0, 0, 1]`.
+/// Angles are in degrees, like everywhere else.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub m: [[f32; 3]; 3],
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mat3 {
+    pub fn identity() -> Self {
+        Self {
+            m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            m: [[1.0, 0.0, x], [0.0, 1.0, y], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn rotation(deg: f32) -> Self {
+        let (s, c) = deg.to_radians().sin_cos();
+        Self {
+            m: [[c, -s, 0.0], [s, c, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self {
+            m: [[sx, 0.0, 0.0], [0.0, sy, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn transform_point(&self, p: &VX2) -> VX2 {
+        let m = &self.m;
+        vx2!(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2]
+        )
+    }
+
+    /// Like `transform_point`, without the translation.
+    pub fn transform_vector(&self, v: &VX2) -> VX2 {
+        let m = &self.m;
+        vx2!(m[0][0] * v.x + m[0][1] * v.y, m[1][0] * v.x + m[1][1] * v.y)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.m[0][0] * self.m[1][1] - self.m[0][1] * self.m[1][0]
+    }
+
+    /// Average factor lengths get scaled by, exact for uniform scaling.
+    pub fn scale_factor(&self) -> f32 {
+        self.determinant().abs().sqrt()
+    }
+
+    /// None only if it squashes everything onto a line or point, tiny scales still invert.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        let inv_det = 1.0 / det;
+        if !det.is_finite() || !inv_det.is_finite() {
+            return None;
+        }
+        let [[a, b, tx], [c, d, ty], _] = self.m;
+        let (ia, ib, ic, id) = (d * inv_det, -b * inv_det, -c * inv_det, a * inv_det);
+        Some(Self {
+            m: [
+                [ia, ib, -(ia * tx + ib * ty)],
+                [ic, id, -(ic * tx + id * ty)],
+                [0.0, 0.0, 1.0],
+            ],
+        })
+    }
+}
+
+/// `a * b` applies `b` first, then `a`.
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, rhs: Mat3) -> Self::Output {
+        let mut m = [[0.0; 3]; 3];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                *v = self.m[r][0] * rhs.m[0][c]
+                    + self.m[r][1] * rhs.m[1][c]
+                    + self.m[r][2] * rhs.m[2][c];
+            }
+        }
+        Mat3 { m }
+    }
+}
+
+impl MulAssign for Mat3 {
+    fn mul_assign(&mut self, rhs: Mat3) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<&VX2> for &Mat3 {
+    type Output = VX2;
+
+    fn mul(self, rhs: &VX2) -> Self::Output {
+        self.transform_point(rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{linal::mat3::Mat3, vx2};
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.001
+    }
+
+    #[test]
+    fn compose_and_invert() {
+        let m = Mat3::translation(10.0, 5.0) * Mat3::rotation(90.0) * Mat3::scale(2.0, 2.0);
+        let p = m.transform_point(&vx2!(1.0, 0.0));
+        assert!(close(p.x, 10.0) && close(p.y, 7.0));
+        assert!(close(m.scale_factor(), 2.0));
+
+        let back = m.inverse().unwrap().transform_point(&p);
+        assert!(close(back.x, 1.0) && close(back.y, 0.0));
+        assert!(Mat3::scale(0.0, 1.0).inverse().is_none());
+
+        // zoomed far out, det 1e-4
+        let small = Mat3::translation(3.0, 4.0) * Mat3::scale(0.01, 0.01);
+        let back = small.inverse().unwrap().transform_point(&vx2!(3.5, 4.25));
+        assert!(close(back.x, 50.0) && close(back.y, 25.0));
+        assert!(Mat3::scale(f32::NAN, 1.0).inverse().is_none());
+        assert!(Mat3::scale(f32::INFINITY, 1.0).inverse().is_none());
+    }
+}
@@ -1,2 +1,2 @@
+pub mod mat3;
 pub mod vertx2;
-
@@ -0,0 +1,106 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+    vx2,
+};
+
+use super::{
+    flatten,
+    polygon::{fill_outline, model_tolerance, stroke_outline},
+};
+
+/// What closes an arc.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ArcKind {
+    /// stroked open, filled up to the chord
+    #[default]
+    Open,
+    /// closed by the line between the end points
+    Chord,
+    /// closed through the center, a pie slice
+    Pie,
+}
+
+/// Part of a circle, angles in degrees, 0 points right and positive `sweep` turns clockwise.
+#[derive(Debug, Clone, Copy)]
+pub struct Arc2d {
+    pub center: VX2,
+    pub r: f32,
+    pub start: f32,
+    pub sweep: f32,
+    pub kind: ArcKind,
+    stroke: Color,
+    fill: Color,
+}
+
+impl Arc2d {
+    pub fn new(center: VX2, r: f32, start: f32, sweep: f32) -> Self {
+        Self {
+            center,
+            r,
+            start,
+            sweep,
+            kind: ArcKind::default(),
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    /// Pie slice, same as `new` with `ArcKind::Pie`.
+    pub fn pie(center: VX2, r: f32, start: f32, sweep: f32) -> Self {
+        Self::new(center, r, start, sweep).with_kind(ArcKind::Pie)
+    }
+    pub fn with_kind(mut self, kind: ArcKind) -> Self {
+        self.kind = kind;
+        self
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    /// The arc as a polyline, with the center last for pie slices.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let mut out = Vec::new();
+        let sweep = self.sweep.clamp(-360.0, 360.0);
+        let radii = vx2!(self.r, self.r);
+        flatten::arc(
+            &mut out,
+            &self.center,
+            &radii,
+            0.0,
+            self.start,
+            sweep,
+            tolerance,
+        );
+        if self.kind == ArcKind::Pie && sweep.abs() < 360.0 {
+            out.push(self.center);
+        }
+        out
+    }
+}
+
+impl Render for Arc2d {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let points = self.points(model_tolerance(renderer));
+        let closed = self.kind != ArcKind::Open || self.sweep.abs() >= 360.0;
+        stroke_outline(renderer, &points, closed, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
+    }
+}
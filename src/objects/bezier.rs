@@ -0,0 +1,226 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+};
+
+use super::{
+    flatten,
+    polygon::{fill_outline, model_tolerance, stroke_outline},
+};
+
+/// Quadratic Bézier curve, fills the area between the curve and the line `p0` to `p2`.
+#[derive(Debug, Clone, Copy)]
+pub struct QuadBezier2d {
+    pub p0: VX2,
+    pub p1: VX2,
+    pub p2: VX2,
+    stroke: Color,
+    fill: Color,
+}
+
+impl QuadBezier2d {
+    pub fn new(p0: VX2, p1: VX2, p2: VX2) -> Self {
+        Self {
+            p0,
+            p1,
+            p2,
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    /// The curve as a polyline, at most `tolerance` away from it.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let mut out = vec![self.p0];
+        flatten::quad(&mut out, &self.p0, &self.p1, &self.p2, tolerance);
+        out
+    }
+}
+
+impl Render for QuadBezier2d {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let points = self.points(model_tolerance(renderer));
+        stroke_outline(renderer, &points, false, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
+    }
+}
+
+/// Cubic Bézier curve, fills the area between the curve and the line `p0` to `p3`.
+#[derive(Debug, Clone, Copy)]
+pub struct CubicBezier2d {
+    pub p0: VX2,
+    pub p1: VX2,
+    pub p2: VX2,
+    pub p3: VX2,
+    stroke: Color,
+    fill: Color,
+}
+
+impl CubicBezier2d {
+    pub fn new(p0: VX2, p1: VX2, p2: VX2, p3: VX2) -> Self {
+        Self {
+            p0,
+            p1,
+            p2,
+            p3,
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    /// The curve as a polyline, at most `tolerance` away from it.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let mut out = vec![self.p0];
+        flatten::cubic(&mut out, &self.p0, &self.p1, &self.p2, &self.p3, tolerance);
+        out
+    }
+}
+
+impl Render for CubicBezier2d {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let points = self.points(model_tolerance(renderer));
+        stroke_outline(renderer, &points, false, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        linal::vertx2::VX2,
+        objects::bezier::{CubicBezier2d, QuadBezier2d},
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    /// Every point and every segment middle is within `tolerance` of the curve `at`.
+    fn follows(points: &[VX2], tolerance: f32, at: impl Fn(f32) -> VX2) -> bool {
+        let curve: Vec<VX2> = (0..=1000).map(|i| at(i as f32 / 1000.0)).collect();
+        let off = |p: VX2| {
+            curve
+                .iter()
+                .map(|c| ((c.x - p.x).powi(2) + (c.y - p.y).powi(2)).sqrt())
+                .fold(f32::MAX, f32::min)
+        };
+        let middles = points
+            .windows(2)
+            .map(|w| vx2!((w[0].x + w[1].x) / 2.0, (w[0].y + w[1].y) / 2.0));
+        points
+            .iter()
+            .copied()
+            .chain(middles)
+            .all(|p| off(p) <= tolerance + 0.05)
+    }
+
+    fn xy(p: VX2) -> (f32, f32) {
+        (p.x, p.y)
+    }
+
+    fn filled(renderer: &Renderer) -> usize {
+        renderer
+            .buffer()
+            .as_slice()
+            .iter()
+            .filter(|p| **p != 0)
+            .count()
+    }
+
+    #[test]
+    fn quad_flatten_and_fill() {
+        let quad = QuadBezier2d::new(vx2!(0.0, 0.0), vx2!(32.0, 64.0), vx2!(64.0, 0.0));
+        let at = |t: f32| {
+            // p0 is at the origin, its weight drops out
+            let (b, c) = (2.0 * t * (1.0 - t), t * t);
+            vx2!(b * 32.0 + c * 64.0, b * 64.0)
+        };
+        for tolerance in [0.1, 0.5, 2.0] {
+            let points = quad.points(tolerance);
+            assert!(points.len() > 2);
+            let ends = (xy(points[0]), xy(*points.last().unwrap()));
+            assert_eq!(ends, (xy(quad.p0), xy(quad.p2)));
+            assert!(follows(&points, tolerance, at), "{tolerance}");
+        }
+
+        let mut renderer = Renderer::new(64, 64);
+        quad.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        // a parabola segment is 2/3 of the box around it
+        let area = 2.0 / 3.0 * 64.0 * 32.0;
+        assert!((filled(&renderer) as f32 - area).abs() < area * 0.03);
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 64 + x];
+        assert_ne!(px(32, 28), 0);
+        assert_eq!((px(32, 34), px(4, 16)), (0, 0));
+    }
+
+    #[test]
+    fn cubic_flatten_and_fill() {
+        let (p0, p1, p2, p3) = (
+            vx2!(0.0, 0.0),
+            vx2!(0.0, 48.0),
+            vx2!(64.0, 48.0),
+            vx2!(64.0, 0.0),
+        );
+        let cubic = CubicBezier2d::new(p0, p1, p2, p3);
+        // x = 64 (3t² - 2t³), y = 144 t (1 - t)
+        let at = |t: f32| {
+            vx2!(
+                64.0 * (3.0 * t * t - 2.0 * t * t * t),
+                144.0 * t * (1.0 - t)
+            )
+        };
+        for tolerance in [0.1, 0.5, 2.0] {
+            let points = cubic.points(tolerance);
+            let ends = (xy(points[0]), xy(*points.last().unwrap()));
+            assert_eq!(ends, (xy(p0), xy(p3)));
+            assert!(follows(&points, tolerance, at), "{tolerance}");
+        }
+
+        let mut renderer = Renderer::new(64, 64);
+        cubic.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        // the integral of y dx from 0 to 1
+        let area = 144.0 * 64.0 * 6.0 / 30.0;
+        assert!((filled(&renderer) as f32 - area).abs() < area * 0.03);
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 64 + x];
+        assert_ne!(px(32, 33), 0);
+        assert_eq!((px(32, 38), px(2, 20)), (0, 0));
+    }
+}
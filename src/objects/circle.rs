@@ -1,6 +1,5 @@
 use crate::{
-    buffer::Buffer,
-    colors::color::Color,
+    colors::{color::Color, paint::Paint},
     constants::DEFAULT_FOREGROUND,
     linal::vertx2::VX2,
     renderer::two_d::{Render, Renderer},
@@ -13,6 +12,8 @@ use super::{
         epa::epa,
         gjk::{furthest_circle, furthest_polygon, gjk_for_epa},
     },
+    flatten,
+    polygon::{fill_outline, model_tolerance, stroke_outline},
 };
 
 #[derive(Clone, Copy)]
@@ -38,80 +39,43 @@ impl Circle {
     pub fn set_stroke<C: Into<Color> + Copy>(&mut self, stroke: C) {
         self.stroke = stroke.into()
     }
+
+    /// The outline as a closed polyline, the last point is not repeated.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let mut out = Vec::new();
+        flatten::arc(
+            &mut out,
+            &self.pos,
+            &vx2!(self.r, self.r),
+            0.0,
+            0.0,
+            360.0,
+            tolerance,
+        );
+        out.pop();
+        out
+    }
 }
 
 impl Render for Circle {
     fn draw(&self, renderer: &mut Renderer) {
-        mid_point_circle(renderer.buffer_mut(), self, self.stroke);
+        self.draw_clr(renderer, self.stroke);
     }
     fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        mid_point_circle(renderer.buffer_mut(), self, c.into());
+        let points = self.points(model_tolerance(renderer));
+        stroke_outline(renderer, &points, true, c.into());
     }
 
     fn fill(&self, renderer: &mut Renderer) {
-        fill_circle_brute_force(renderer.buffer_mut(), &self, self.fill);
+        self.fill_clr(renderer, self.fill);
     }
     fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        fill_circle_brute_force(renderer.buffer_mut(), &self, c.into());
-    }
-}
-
-fn draw_circle(buffer: &mut Buffer<u32>, center: &VX2, pos: &VX2, color: u32) {
-    buffer.set_xy((center.x + pos.x) as i32, (center.y + pos.y) as i32, color);
-    buffer.set_xy((center.x - pos.x) as i32, (center.y + pos.y) as i32, color);
-    buffer.set_xy((center.x + pos.x) as i32, (center.y - pos.y) as i32, color);
-    buffer.set_xy((center.x - pos.x) as i32, (center.y - pos.y) as i32, color);
-    buffer.set_xy((center.x + pos.y) as i32, (center.y + pos.x) as i32, color);
-    buffer.set_xy((center.x - pos.y) as i32, (center.y + pos.x) as i32, color);
-    buffer.set_xy((center.x + pos.y) as i32, (center.y - pos.x) as i32, color);
-    buffer.set_xy((center.x - pos.y) as i32, (center.y - pos.x) as i32, color);
-}
-pub fn mid_point_circle<C: Into<Color> + Copy>(
-    buffer: &mut Buffer<u32>,
-    circle: &Circle,
-    color: C,
-) {
-    let radius = circle.r as i32;
-    let mut f = 1 - radius;
-    let mut x = 0;
-    let mut y = radius;
-
-    let mut ddf_x = 1;
-    let mut ddf_y = -2 * radius;
-
-    let clr: u32 = color.into().into();
-    buffer.set_xy(circle.pos.x as i32, (circle.pos.y as i32) + radius, clr);
-    buffer.set_xy(circle.pos.x as i32, (circle.pos.y as i32) - radius, clr);
-    buffer.set_xy((circle.pos.x as i32) + radius, circle.pos.y as i32, clr);
-    buffer.set_xy((circle.pos.x as i32) - radius, circle.pos.y as i32, clr);
-
-    while x < y {
-        if f >= 0 {
-            y -= 1;
-            ddf_y += 2;
-            f += ddf_y;
-        }
-        x += 1;
-        ddf_x += 2;
-        f += ddf_x;
-        draw_circle(buffer, &circle.pos, &vx2!(x as f32, y as f32), clr);
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
     }
-}
-
-pub fn fill_circle_brute_force<C: Into<Color> + Copy>(
-    buffer: &mut Buffer<u32>,
-    circle: &Circle,
-    color: C,
-) {
-    let radius = circle.r as i32;
-    let clr: u32 = color.into().into();
-
-    for y in -radius..=radius {
-        for x in -radius..=radius {
-            if x * x + y * y <= radius * radius {
-                buffer.set_xy(circle.pos.x as i32 + x, circle.pos.y as i32 + y, clr);
-            }
-        }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
     }
 }
 
@@ -116,6 +116,10 @@ pub fn gjk<A: Sized + Vertices + SupportV, B: Sized + Vertices + SupportV>(
 ) -> bool {
     let verts1 = obj_1.vertices();
     let verts2 = obj_2.vertices();
+    // an empty polygon has nothing to touch
+    if verts1.is_empty() || verts2.is_empty() {
+        return false;
+    }
 
     let mut dir = average_point(&verts1) - &average_point(&verts2);
     let mut simplex = Vec::with_capacity(3);
@@ -197,6 +201,9 @@ pub fn gjk_for_epa<A: Sized + Vertices + SupportV, B: Sized + Vertices + Support
 ) -> Option<Vec<EpaVertex>> {
     let verts1 = obj_1.vertices();
     let verts2 = obj_2.vertices();
+    if verts1.is_empty() || verts2.is_empty() {
+        return None;
+    }
 
     let mut dir = average_point(&verts1) - &average_point(&verts2);
     let mut simplex = Vec::with_capacity(3);
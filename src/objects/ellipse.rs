@@ -0,0 +1,134 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+};
+
+use super::{
+    flatten,
+    polygon::{fill_outline, model_tolerance, stroke_outline},
+    utils::{BBox2d, Bounds},
+};
+
+/// Ellipse around `center`, `radii` along its own axes, rotated by `rotation` degrees.
+#[derive(Debug, Clone, Copy)]
+pub struct Ellipse {
+    pub center: VX2,
+    pub radii: VX2,
+    pub rotation: f32,
+    stroke: Color,
+    fill: Color,
+}
+
+impl Ellipse {
+    pub fn new(center: VX2, radii: VX2) -> Self {
+        Self {
+            center,
+            radii,
+            rotation: 0.0,
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    pub fn set_rotation(&mut self, deg: f32) {
+        self.rotation = deg;
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    /// The outline as a closed polyline, the last point is not repeated.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let mut out = Vec::new();
+        flatten::arc(
+            &mut out,
+            &self.center,
+            &self.radii,
+            self.rotation,
+            0.0,
+            360.0,
+            tolerance,
+        );
+        out.pop();
+        out
+    }
+}
+
+impl BBox2d for Ellipse {
+    fn bbox(&self) -> Bounds {
+        let (s, c) = self.rotation.to_radians().sin_cos();
+        let (rx, ry) = (self.radii.x, self.radii.y);
+        let half_w = ((rx * c).powi(2) + (ry * s).powi(2)).sqrt();
+        let half_h = ((rx * s).powi(2) + (ry * c).powi(2)).sqrt();
+        Bounds {
+            min_x: self.center.x - half_w,
+            max_x: self.center.x + half_w,
+            min_y: self.center.y - half_h,
+            max_y: self.center.y + half_h,
+        }
+    }
+}
+
+impl Render for Ellipse {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let points = self.points(model_tolerance(renderer));
+        stroke_outline(renderer, &points, true, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::{arc::Arc2d, ellipse::Ellipse},
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    fn filled(renderer: &Renderer) -> usize {
+        renderer
+            .buffer()
+            .as_slice()
+            .iter()
+            .filter(|p| **p != 0)
+            .count()
+    }
+
+    #[test]
+    fn rotated_ellipse_and_pie_fill() {
+        let mut renderer = Renderer::new(64, 64);
+        let mut ellipse = Ellipse::new(vx2!(32.0, 32.0), vx2!(20.0, 8.0));
+        ellipse.set_rotation(90.0);
+        ellipse.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        let area = std::f32::consts::PI * 20.0 * 8.0;
+        assert!((filled(&renderer) as f32 - area).abs() < area * 0.03);
+        // standing up after a quarter turn
+        assert_ne!(renderer.buffer().as_slice()[14 * 64 + 32], 0);
+        assert_eq!(renderer.buffer().as_slice()[32 * 64 + 14], 0);
+
+        renderer.clear_background(0u32);
+        Arc2d::pie(vx2!(32.0, 32.0), 16.0, 0.0, 90.0).fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        let area = std::f32::consts::PI * 16.0 * 16.0 / 4.0;
+        assert!((filled(&renderer) as f32 - area).abs() < area * 0.05);
+        // clockwise from the right, so the bottom right quarter
+        assert_ne!(renderer.buffer().as_slice()[40 * 64 + 40], 0);
+        assert_eq!(renderer.buffer().as_slice()[24 * 64 + 40], 0);
+    }
+}
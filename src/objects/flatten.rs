@@ -0,0 +1,70 @@
+use crate::{linal::vertx2::VX2, vx2};
+
+/// Max distance in pixels between a curve and the lines it gets drawn with.
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// Appends points along the quadratic curve from `p0`, `p0` itself is not added.
+pub fn quad(out: &mut Vec<VX2>, p0: &VX2, p1: &VX2, p2: &VX2, tolerance: f32) {
+    let dd = (*p0 - &(*p1 * 2.0) + p2).length();
+    let n = segments(dd * 0.25, tolerance);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        let mt = 1.0 - t;
+        out.push(*p0 * (mt * mt) + &(*p1 * (2.0 * mt * t)) + &(*p2 * (t * t)));
+    }
+}
+
+/// Appends points along the cubic curve from `p0`, `p0` itself is not added.
+pub fn cubic(out: &mut Vec<VX2>, p0: &VX2, p1: &VX2, p2: &VX2, p3: &VX2, tolerance: f32) {
+    let dd1 = (*p0 - &(*p1 * 2.0) + p2).length();
+    let dd2 = (*p1 - &(*p2 * 2.0) + p3).length();
+    let n = segments(dd1.max(dd2) * 0.75, tolerance);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        let mt = 1.0 - t;
+        out.push(
+            *p0 * (mt * mt * mt)
+                + &(*p1 * (3.0 * mt * mt * t))
+                + &(*p2 * (3.0 * mt * t * t))
+                + &(*p3 * (t * t * t)),
+        );
+    }
+}
+
+/// Segment count for a curve with the given second derivative bound (Wang's formula).
+fn segments(curvature: f32, tolerance: f32) -> usize {
+    ((curvature / tolerance.max(f32::EPSILON)).sqrt().ceil() as usize).clamp(1, 1024)
+}
+
+/// Point on an ellipse, `angle` and `rotation` in degrees, rotating like `Rectangle` does.
+pub fn ellipse_point(center: &VX2, radii: &VX2, rotation: f32, angle: f32) -> VX2 {
+    let (s, c) = angle.to_radians().sin_cos();
+    let (x, y) = (radii.x * c, radii.y * s);
+    let (rs, rc) = rotation.to_radians().sin_cos();
+    vx2!(x * rc - y * rs + center.x, x * rs + y * rc + center.y)
+}
+
+/// Appends points along the elliptic arc, start and end included.
+/// Angles in degrees, positive `sweep` turns clockwise on screen.
+pub fn arc(
+    out: &mut Vec<VX2>,
+    center: &VX2,
+    radii: &VX2,
+    rotation: f32,
+    start: f32,
+    sweep: f32,
+    tolerance: f32,
+) {
+    let r = radii.x.abs().max(radii.y.abs());
+    // angle step that keeps the chord within tolerance of the circle
+    let step = if r > tolerance {
+        2.0 * (1.0 - tolerance / r).acos().to_degrees()
+    } else {
+        90.0
+    };
+    let n = ((sweep.abs() / step.max(0.1)).ceil() as usize).clamp(1, 4096);
+    for i in 0..=n {
+        let angle = start + sweep * i as f32 / n as f32;
+        out.push(ellipse_point(center, radii, rotation, angle));
+    }
+}
@@ -1,5 +1,4 @@
 use crate::{
-    buffer::Buffer,
     colors::color::Color,
     linal::vertx2::VX2,
     renderer::two_d::{Render, Renderer},
@@ -12,7 +11,8 @@ use super::{
         epa::{EpaResult, epa},
         gjk::{furthest_polygon, gjk_for_epa},
     },
-    utils::top_left_line,
+    stroke::stroke_polyline,
+    utils::{Bounds, top_left_line},
 };
 
 #[derive(Clone, Debug)]
@@ -63,16 +63,25 @@ impl Line2d {
 impl Render for Line2d {
     fn draw(&self, renderer: &mut Renderer) {
         if let Some(clr) = self.clr {
-            bresenham(renderer.buffer_mut(), &self.p1, &self.p2, clr.into());
+            self.draw_clr(renderer, clr);
         }
     }
     fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        bresenham(renderer.buffer_mut(), &self.p1, &self.p2, c.into());
+        let (p1, p2) = (renderer.to_screen(&self.p1), renderer.to_screen(&self.p2));
+        stroke_polyline(renderer, &[p1, p2], false, c.into());
     }
 
     fn draw_renderer(&self, renderer: &mut Renderer) {
-        let clr: u32 = renderer.stroke().into();
-        bresenham(renderer.buffer_mut(), &self.p1, &self.p2, clr);
+        self.draw_clr(renderer, renderer.stroke());
+    }
+}
+
+/// One pixel wide line in screen coordinates, anti aliased if the renderer says so.
+pub fn draw_line(renderer: &mut Renderer, p1: &VX2, p2: &VX2, clr: u32) {
+    if renderer.anti_aliasing() {
+        wu_line(renderer, p1, p2, clr);
+    } else {
+        bresenham(renderer, p1, p2, clr);
     }
 }
 
@@ -106,8 +115,56 @@ impl Render for Line2d {
 //    }
 //}
 
+/// Cuts the segment down to the part inside `bounds` (Liang-Barsky), None if nothing is left.
+pub fn clip_segment(p1: &VX2, p2: &VX2, bounds: &Bounds) -> Option<(VX2, VX2)> {
+    let d = *p2 - p1;
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (p, q) in [
+        (-d.x, p1.x - bounds.min_x),
+        (d.x, bounds.max_x - p1.x),
+        (-d.y, p1.y - bounds.min_y),
+        (d.y, bounds.max_y - p1.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some((*p1 + &(d * t0), *p1 + &(d * t1)))
+}
+
+/// Renderer clip as bounds around pixel centers, grown by `margin` pixels.
+fn clip_bounds(renderer: &Renderer, margin: f32) -> Bounds {
+    let (x0, y0, x1, y1) = renderer.clip_px();
+    Bounds {
+        min_x: x0 as f32 - 0.5 - margin,
+        max_x: x1 as f32 - 0.5 + margin,
+        min_y: y0 as f32 - 0.5 - margin,
+        max_y: y1 as f32 - 0.5 + margin,
+    }
+}
+
 /// Bresenham line algorithm (handles all octants).
-pub fn bresenham(buffer: &mut Buffer<u32>, p1: &VX2, p2: &VX2, clr: u32) {
+pub fn bresenham(renderer: &mut Renderer, p1: &VX2, p2: &VX2, clr: u32) {
+    let Some((p1, p2)) = clip_segment(p1, p2, &clip_bounds(renderer, 0.0)) else {
+        return;
+    };
+    bresenham_points(&p1, &p2, |x, y, _| renderer.blend_pixel(x, y, clr, 1.0));
+}
+
+/// The pixels of [`bresenham`], `plot` gets x, y and a coverage of 1.0.
+pub(crate) fn bresenham_points<F: FnMut(i32, i32, f32)>(p1: &VX2, p2: &VX2, mut plot: F) {
     let dx = (p2.x - p1.x).abs();
     let dy = (p2.y - p1.y).abs();
     // Determine if the line is steep. If so, we'll swap x and y axes.
@@ -136,9 +193,9 @@ pub fn bresenham(buffer: &mut Buffer<u32>, p1: &VX2, p2: &VX2, clr: u32) {
         let i_y = y.round() as i32;
 
         if steep {
-            buffer.set_xy(i_y, x as i32, clr); //swapped back
+            plot(i_y, x as i32, 1.0); //swapped back
         } else {
-            buffer.set_xy(x as i32, i_y, clr);
+            plot(x as i32, i_y, 1.0);
         }
         err -= dy;
         if err < 0.0 {
@@ -148,6 +205,70 @@ pub fn bresenham(buffer: &mut Buffer<u32>, p1: &VX2, p2: &VX2, clr: u32) {
     }
 }
 
+fn fpart(v: f32) -> f32 {
+    v - v.floor()
+}
+
+/// Xiaolin Wu's line algorithm, blends into whatever is in the buffer already.
+pub fn wu_line(renderer: &mut Renderer, p1: &VX2, p2: &VX2, clr: u32) {
+    let Some((p1, p2)) = clip_segment(p1, p2, &clip_bounds(renderer, 1.0)) else {
+        return;
+    };
+    wu_points(&p1, &p2, |x, y, coverage| {
+        renderer.blend_pixel(x, y, clr, coverage)
+    });
+}
+
+/// The pixels and coverages of [`wu_line`].
+pub(crate) fn wu_points<F: FnMut(i32, i32, f32)>(p1: &VX2, p2: &VX2, mut plot_xy: F) {
+    let steep = (p2.y - p1.y).abs() > (p2.x - p1.x).abs();
+    let (mut x0, mut y0, mut x1, mut y1) = if steep {
+        (p1.y, p1.x, p2.y, p2.x)
+    } else {
+        (p1.x, p1.y, p2.x, p2.y)
+    };
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+    let mut plot = |x: i32, y: i32, coverage: f32| {
+        if steep {
+            plot_xy(y, x, coverage);
+        } else {
+            plot_xy(x, y, coverage);
+        }
+    };
+
+    // first end point
+    let x_end = x0.round();
+    let y_end = y0 + gradient * (x_end - x0);
+    let x_gap = 1.0 - fpart(x0 + 0.5);
+    let x_px1 = x_end as i32;
+    let y_px1 = y_end.floor() as i32;
+    plot(x_px1, y_px1, (1.0 - fpart(y_end)) * x_gap);
+    plot(x_px1, y_px1 + 1, fpart(y_end) * x_gap);
+    let mut inter_y = y_end + gradient;
+
+    // second end point
+    let x_end = x1.round();
+    let y_end = y1 + gradient * (x_end - x1);
+    let x_gap = fpart(x1 + 0.5);
+    let x_px2 = x_end as i32;
+    let y_px2 = y_end.floor() as i32;
+    plot(x_px2, y_px2, (1.0 - fpart(y_end)) * x_gap);
+    plot(x_px2, y_px2 + 1, fpart(y_end) * x_gap);
+
+    for x in x_px1 + 1..x_px2 {
+        let y = inter_y.floor() as i32;
+        plot(x, y, 1.0 - fpart(inter_y));
+        plot(x, y + 1, fpart(inter_y));
+        inter_y += gradient;
+    }
+}
+
 impl Vertices for Line2d {
     fn vertices(&self) -> Vec<VX2> {
         vec![self.p1, self.p2]
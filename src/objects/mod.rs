@@ -2,10 +2,19 @@ use collision::{epa::EpaResult, gjk::gjk};
 
 use crate::linal::vertx2::VX2;
 
+pub mod arc;
+pub mod bezier;
 pub mod circle;
 pub mod collision;
+pub mod ellipse;
+pub mod flatten;
 pub mod line;
+pub mod path;
+pub mod polygon;
+pub mod raster;
 pub mod rectangle;
+pub mod rounded_rect;
+pub mod stroke;
 pub mod tri;
 pub mod utils;
 
@@ -0,0 +1,300 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+    vx2,
+};
+
+use super::{
+    flatten,
+    polygon::{FillRule, model_tolerance},
+    raster::paint_contours,
+    stroke::stroke_polylines,
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Verb {
+    MoveTo(VX2),
+    LineTo(VX2),
+    QuadTo(VX2, VX2),
+    CubicTo(VX2, VX2, VX2),
+    /// circular arc, the path goes straight to its start first
+    Arc {
+        center: VX2,
+        r: f32,
+        start: f32,
+        sweep: f32,
+    },
+    Close,
+}
+
+/// Outlines built from lines and curves, any number of sub paths.
+/// Filling closes every sub path, stroking only the ones ended with `close`.
+#[derive(Debug, Clone)]
+pub struct Path {
+    verbs: Vec<Verb>,
+    /// start of the current sub path
+    start: Option<VX2>,
+    current: Option<VX2>,
+    pub fill_rule: FillRule,
+    stroke: Color,
+    fill: Color,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self {
+            verbs: Vec::new(),
+            start: None,
+            current: None,
+            fill_rule: FillRule::default(),
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    pub fn with_fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.verbs.is_empty()
+    }
+    /// Where the next segment starts, None before the first `move_to`.
+    pub fn current_point(&self) -> Option<VX2> {
+        self.current
+    }
+
+    /// Starts a new sub path.
+    pub fn move_to(&mut self, p: VX2) -> &mut Self {
+        self.verbs.push(Verb::MoveTo(p));
+        self.start = Some(p);
+        self.current = Some(p);
+        self
+    }
+
+    /// Straight line, starts a sub path at `p` if there is none.
+    pub fn line_to(&mut self, p: VX2) -> &mut Self {
+        if self.current.is_none() {
+            return self.move_to(p);
+        }
+        self.verbs.push(Verb::LineTo(p));
+        self.current = Some(p);
+        self
+    }
+
+    /// Quadratic Bézier curve with control point `c`.
+    pub fn quad_to(&mut self, c: VX2, p: VX2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(c);
+        }
+        self.verbs.push(Verb::QuadTo(c, p));
+        self.current = Some(p);
+        self
+    }
+
+    /// Cubic Bézier curve with control points `c1` and `c2`.
+    pub fn cubic_to(&mut self, c1: VX2, c2: VX2, p: VX2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(c1);
+        }
+        self.verbs.push(Verb::CubicTo(c1, c2, p));
+        self.current = Some(p);
+        self
+    }
+
+    /// Rounds the corner at `corner` on the way to `to`, like the canvas `arcTo`.
+    /// Goes straight to where the arc touches the line to `corner`, then along an arc of
+    /// `radius` that ends on the line from `corner` to `to`. `to` itself is not reached.
+    pub fn arc_to(&mut self, corner: VX2, to: VX2, radius: f32) -> &mut Self {
+        let Some(from) = self.current else {
+            return self.move_to(corner);
+        };
+        let (d1, d2) = (from - &corner, to - &corner);
+        let cross = d1.x * d2.y - d1.y * d2.x;
+        if radius <= 0.0 || d1.length() == 0.0 || d2.length() == 0.0 || cross.abs() < 1e-6 {
+            return self.line_to(corner);
+        }
+        let (d1, d2) = (d1.normalized_clone(), d2.normalized_clone());
+        let half = d1.dot(&d2).clamp(-1.0, 1.0).acos() * 0.5;
+        let t1 = corner + &(d1 * (radius / half.tan()));
+        let t2 = corner + &(d2 * (radius / half.tan()));
+        let center = corner + &((d1 + &d2).normalized_clone() * (radius / half.sin()));
+        let angle = |p: &VX2| (p.y - center.y).atan2(p.x - center.x).to_degrees();
+        let (start, end) = (angle(&t1), angle(&t2));
+        let sweep = (end - start + 180.0).rem_euclid(360.0) - 180.0;
+
+        self.verbs.push(Verb::Arc {
+            center,
+            r: radius,
+            start,
+            sweep,
+        });
+        self.current = Some(t2);
+        self
+    }
+
+    /// Closes the sub path with a line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        if self.current.is_some() {
+            self.verbs.push(Verb::Close);
+            self.current = self.start;
+        }
+        self
+    }
+
+    /// Sub paths as polylines at most `tolerance` away from the curves, with whether they
+    /// were closed. Closed ones don't repeat their first point.
+    pub fn flatten(&self, tolerance: f32) -> Vec<(Vec<VX2>, bool)> {
+        let mut out: Vec<(Vec<VX2>, bool)> = Vec::new();
+        let mut points: Vec<VX2> = Vec::new();
+        let mut finish = |points: &mut Vec<VX2>, closed: bool| {
+            let repeats = |a: &VX2, b: &VX2| (*a - b).length() <= f32::EPSILON;
+            if closed && points.len() > 1 && repeats(&points[0], &points[points.len() - 1]) {
+                points.pop();
+            }
+            if points.len() > 1 {
+                out.push((std::mem::take(points), closed));
+            }
+            points.clear();
+        };
+        for verb in self.verbs.iter() {
+            let last = points.last().copied().unwrap_or_default();
+            match *verb {
+                Verb::MoveTo(p) => {
+                    finish(&mut points, false);
+                    points.push(p);
+                }
+                Verb::LineTo(p) => points.push(p),
+                Verb::QuadTo(c, p) => flatten::quad(&mut points, &last, &c, &p, tolerance),
+                Verb::CubicTo(c1, c2, p) => {
+                    flatten::cubic(&mut points, &last, &c1, &c2, &p, tolerance)
+                }
+                Verb::Arc {
+                    center,
+                    r,
+                    start,
+                    sweep,
+                } => {
+                    let radii = vx2!(r, r);
+                    flatten::arc(&mut points, &center, &radii, 0.0, start, sweep, tolerance);
+                }
+                Verb::Close => {
+                    let start = points.first().copied();
+                    finish(&mut points, true);
+                    // anything after a close starts where the closed sub path did
+                    points.extend(start);
+                }
+            }
+        }
+        finish(&mut points, false);
+        out
+    }
+}
+
+impl Render for Path {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let lines: Vec<(Vec<VX2>, bool)> = self
+            .flatten(model_tolerance(renderer))
+            .into_iter()
+            .map(|(points, closed)| {
+                (
+                    points.iter().map(|p| renderer.to_screen(p)).collect(),
+                    closed,
+                )
+            })
+            .collect();
+        let lines: Vec<(&[VX2], bool)> = lines.iter().map(|(p, c)| (p.as_slice(), *c)).collect();
+        stroke_polylines(renderer, &lines, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let contours: Vec<Vec<VX2>> = self
+            .flatten(model_tolerance(renderer))
+            .into_iter()
+            .map(|(points, _)| points.iter().map(|p| renderer.to_screen(p)).collect())
+            .collect();
+        paint_contours(renderer, &contours, self.fill_rule, paint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::{path::Path, polygon::FillRule},
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    #[test]
+    fn holes_curves_and_arcs() {
+        let mut renderer = Renderer::new(32, 32);
+        let mut path = Path::new().with_fill_rule(FillRule::EvenOdd);
+        path.move_to(vx2!(2.0, 2.0))
+            .line_to(vx2!(30.0, 2.0))
+            .line_to(vx2!(30.0, 30.0))
+            .line_to(vx2!(2.0, 30.0))
+            .close()
+            .move_to(vx2!(10.0, 10.0))
+            .line_to(vx2!(22.0, 10.0))
+            .line_to(vx2!(22.0, 22.0))
+            .line_to(vx2!(10.0, 22.0))
+            .close();
+        assert_eq!(path.flatten(0.25).len(), 2);
+        path.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        let px = |r: &Renderer, x: usize, y: usize| r.buffer().as_slice()[y * 32 + x];
+        assert_eq!(px(&renderer, 5, 5), 0xFFFFFFFF);
+        assert_eq!(px(&renderer, 16, 16), 0);
+        let filled = renderer
+            .buffer()
+            .as_slice()
+            .iter()
+            .filter(|p| **p != 0)
+            .count();
+        assert_eq!(filled, 28 * 28 - 12 * 12);
+
+        // rounded corner: the arc ends up tangent to both lines
+        let mut path = Path::new();
+        path.move_to(vx2!(0.0, 0.0))
+            .arc_to(vx2!(20.0, 0.0), vx2!(20.0, 20.0), 8.0);
+        assert!((path.current_point().unwrap() - &vx2!(20.0, 8.0)).length() < 1e-4);
+        let (points, closed) = &path.flatten(0.1)[0];
+        assert!(!closed);
+        assert!(
+            points
+                .iter()
+                .all(|p| (p.x <= 12.0 + 1e-4) || (*p - &vx2!(12.0, 8.0)).length() <= 8.0 + 1e-3)
+        );
+
+        // curves stay inside their control polygon
+        renderer.clear_background(0u32);
+        let mut path = Path::new();
+        path.move_to(vx2!(4.0, 28.0))
+            .quad_to(vx2!(16.0, 0.0), vx2!(28.0, 28.0))
+            .cubic_to(vx2!(20.0, 20.0), vx2!(12.0, 20.0), vx2!(4.0, 28.0));
+        path.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_ne!(px(&renderer, 16, 14), 0);
+        assert_eq!(px(&renderer, 16, 8), 0);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+    vx2,
+};
+
+use super::{
+    Collision, SupportV, Vertices,
+    collision::{
+        epa::{EpaResult, epa},
+        gjk::{furthest_polygon, gjk_for_epa},
+    },
+    flatten::DEFAULT_TOLERANCE,
+    raster::paint_contours,
+    stroke::stroke_polyline,
+    utils::{BBox2d, Bounds, max_of_n, min_of_n},
+};
+
+/// Decides which parts of a self intersecting or nested outline are inside.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FillRule {
+    /// inside where a ray crosses an odd number of edges, overlaps become holes
+    EvenOdd,
+    /// inside where the edges don't cancel out, overlaps stay filled
+    #[default]
+    NonZero,
+}
+
+/// Any closed outline, concave and self intersecting ones included.
+/// Collision works on the convex hull of the vertices, so it is exact for convex polygons only.
+#[derive(Debug, Clone)]
+pub struct Polygon2d {
+    pub verts: Vec<VX2>,
+    pub fill_rule: FillRule,
+    stroke: Color,
+    fill: Color,
+}
+
+impl Polygon2d {
+    pub fn new(verts: Vec<VX2>) -> Self {
+        Self {
+            verts,
+            fill_rule: FillRule::default(),
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+
+    pub fn with_fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    pub fn translate(&mut self, offset: &VX2) {
+        for v in self.verts.iter_mut() {
+            *v += offset;
+        }
+    }
+
+    pub fn rotate(&self, rot_deg: f32, pivot: &VX2) -> Self {
+        Self {
+            verts: self
+                .verts
+                .iter()
+                .map(|v| (v - pivot).rotation(rot_deg) + pivot)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Signed area, positive for clockwise vertices on screen (y down).
+    pub fn area(&self) -> f32 {
+        let n = self.verts.len();
+        (0..n)
+            .map(|i| {
+                let (a, b) = (&self.verts[i], &self.verts[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f32>()
+            * 0.5
+    }
+
+    /// True if every corner turns the same way and the outline doesn't cross itself.
+    pub fn is_convex(&self) -> bool {
+        let n = self.verts.len();
+        if n < 3 {
+            return false;
+        }
+        let mut sign = 0.0;
+        let mut turned = 0.0;
+        for i in 0..n {
+            let (a, b, c) = (
+                self.verts[i],
+                self.verts[(i + 1) % n],
+                self.verts[(i + 2) % n],
+            );
+            let (ab, bc) = (b - &a, c - &b);
+            let cross = ab.x * bc.y - ab.y * bc.x;
+            if cross.abs() < f32::EPSILON {
+                continue;
+            }
+            if sign == 0.0 {
+                sign = cross.signum();
+            } else if cross.signum() != sign {
+                return false;
+            }
+            turned += cross.atan2(ab.dot(&bc));
+        }
+        // a star turns the same way at every corner too, but goes around more than once
+        sign != 0.0 && turned.abs() < std::f32::consts::TAU + 0.01
+    }
+
+    fn projected(&self, renderer: &Renderer) -> Vec<VX2> {
+        self.verts.iter().map(|v| renderer.to_screen(v)).collect()
+    }
+}
+
+impl BBox2d for Polygon2d {
+    fn bbox(&self) -> Bounds {
+        let xs: Vec<f32> = self.verts.iter().map(|v| v.x).collect();
+        let ys: Vec<f32> = self.verts.iter().map(|v| v.y).collect();
+        Bounds {
+            min_x: min_of_n(&xs).unwrap_or_default(),
+            max_x: max_of_n(&xs).unwrap_or_default(),
+            min_y: min_of_n(&ys).unwrap_or_default(),
+            max_y: max_of_n(&ys).unwrap_or_default(),
+        }
+    }
+}
+
+impl Render for Polygon2d {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let verts = self.projected(renderer);
+        stroke_polyline(renderer, &verts, true, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let verts = self.projected(renderer);
+        paint_contours(renderer, &[verts], self.fill_rule, paint);
+    }
+}
+
+/// Tolerance for flattening curves in model space, so they stay smooth on screen.
+pub(crate) fn model_tolerance(renderer: &Renderer) -> f32 {
+    DEFAULT_TOLERANCE / renderer.to_screen_scale(1.0).max(f32::EPSILON)
+}
+
+/// Projects a flattened outline (model space) and strokes it.
+pub(crate) fn stroke_outline(renderer: &mut Renderer, points: &[VX2], closed: bool, clr: u32) {
+    let points: Vec<VX2> = points.iter().map(|p| renderer.to_screen(p)).collect();
+    stroke_polyline(renderer, &points, closed, clr);
+}
+
+/// Projects a flattened outline (model space) and fills it, non-zero.
+pub(crate) fn fill_outline(renderer: &mut Renderer, points: &[VX2], paint: &Paint) {
+    let points: Vec<VX2> = points.iter().map(|p| renderer.to_screen(p)).collect();
+    paint_contours(renderer, &[points], FillRule::NonZero, paint);
+}
+
+/// Furthest vertex in `dir`, the origin if there are none.
+fn support(verts: &[VX2], dir: &VX2) -> VX2 {
+    if verts.is_empty() {
+        return vx2!(0.0, 0.0);
+    }
+    verts[furthest_polygon(verts, dir)]
+}
+
+impl Vertices for Polygon2d {
+    fn vertices(&self) -> Vec<VX2> {
+        self.verts.clone()
+    }
+}
+
+impl SupportV for Polygon2d {
+    fn support(&self, dir: &VX2) -> VX2 {
+        support(&self.verts, dir)
+    }
+}
+
+impl Collision for Polygon2d {
+    fn collides_epa<O: Vertices + SupportV + Sized>(&self, with: &O) -> Option<EpaResult> {
+        let simplex = gjk_for_epa(self, with)?;
+        let verts_with = with.vertices();
+        epa(
+            simplex,
+            |dir: &VX2| self.verts[furthest_polygon(&self.verts, dir)],
+            |dir: &VX2| verts_with[furthest_polygon(&verts_with, dir)],
+        )
+    }
+}
+
+impl Vertices for &Polygon2d {
+    fn vertices(&self) -> Vec<VX2> {
+        self.verts.clone()
+    }
+}
+
+impl SupportV for &Polygon2d {
+    fn support(&self, dir: &VX2) -> VX2 {
+        support(&self.verts, dir)
+    }
+}
+
+impl Collision for &Polygon2d {
+    fn collides_epa<O: Vertices + SupportV + Sized>(&self, with: &O) -> Option<EpaResult> {
+        (*self).collides_epa(with)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::{
+            Collision, SupportV,
+            circle::Circle,
+            polygon::{FillRule, Polygon2d},
+            rectangle::Rectangle,
+        },
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    fn star() -> Polygon2d {
+        // five pointed star drawn in one go, the pentagon in the middle is covered twice
+        let points: Vec<_> = (0..5)
+            .map(|i| vx2!(0.0, -7.0).rotation(i as f32 * 144.0) + &vx2!(8.0, 8.0))
+            .collect();
+        Polygon2d::new(points)
+    }
+
+    #[test]
+    fn concave_and_self_intersecting_fill() {
+        let mut renderer = Renderer::new(16, 16);
+        let l_shape = Polygon2d::new(vec![
+            vx2!(0.0, 0.0),
+            vx2!(4.0, 0.0),
+            vx2!(4.0, 6.0),
+            vx2!(8.0, 6.0),
+            vx2!(8.0, 8.0),
+            vx2!(0.0, 8.0),
+        ]);
+        assert!(!l_shape.is_convex());
+        l_shape.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        let filled = renderer
+            .buffer()
+            .as_slice()
+            .iter()
+            .filter(|p| **p != 0)
+            .count();
+        assert_eq!(filled as f32, l_shape.area().abs());
+
+        let center = 8 * 16 + 8;
+        renderer.clear_background(0u32);
+        star()
+            .with_fill_rule(FillRule::EvenOdd)
+            .fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_eq!(renderer.buffer().as_slice()[center], 0);
+        star().fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_eq!(renderer.buffer().as_slice()[center], 0xFFFFFFFF);
+    }
+
+    #[test]
+    fn convex_collision() {
+        let hexagon = Polygon2d::new(
+            (0..6)
+                .map(|i| vx2!(2.0, 0.0).rotation(i as f32 * 60.0))
+                .collect(),
+        );
+        assert!(hexagon.is_convex());
+        assert!(!star().is_convex());
+        assert!(hexagon.collides(&Rectangle::new(vx2!(2.5, 0.0), vx2!(2.0, 2.0))));
+        assert!(!hexagon.collides(&Rectangle::new(vx2!(5.0, 0.0), vx2!(2.0, 2.0))));
+        let hit = hexagon
+            .collides_epa(&Rectangle::new(vx2!(2.5, 0.0), vx2!(2.0, 2.0)))
+            .unwrap();
+        assert!(hit.depth > 0.0);
+
+        // no vertices, nothing to collide with
+        let empty = Polygon2d::new(Vec::new());
+        let around_origin = Rectangle::new(vx2!(0.0, 0.0), vx2!(2.0, 2.0));
+        assert_eq!((empty.support(&vx2!(1.0, 0.0)).x, empty.area()), (0.0, 0.0));
+        assert!(!empty.collides(&around_origin) && !around_origin.collides(&empty));
+        assert!(empty.collides_epa(&hexagon).is_none());
+        assert!(
+            Circle::new(vx2!(0.0, 0.0), 1.0)
+                .collides_epa(&empty)
+                .is_none()
+        );
+    }
+}
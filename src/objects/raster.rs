@@ -0,0 +1,244 @@
+use crate::{
+    colors::paint::Paint,
+    linal::{mat3::Mat3, vertx2::VX2},
+    renderer::two_d::Renderer,
+    vx2,
+};
+
+use super::polygon::FillRule;
+
+/// Scanlines sampled per pixel row with anti aliasing on.
+const SUBSAMPLES: usize = 4;
+
+enum Shade<'p, 'a> {
+    Solid(u32),
+    /// with the screen to model transform
+    Paint(&'p Paint<'a>, Mat3),
+}
+
+impl Shade<'_, '_> {
+    fn blend(&self, renderer: &mut Renderer, x0: i32, x1: i32, y: i32, coverage: f32) {
+        match self {
+            Shade::Solid(clr) if coverage >= 1.0 => renderer.blend_span(y, x0, x1, *clr),
+            Shade::Solid(clr) => renderer.blend_pixel(x0, y, *clr, coverage),
+            Shade::Paint(paint, to_model) => {
+                for x in x0..x1 {
+                    let p = to_model.transform_point(&vx2!(x as f32 + 0.5, y as f32 + 0.5));
+                    renderer.blend_pixel(x, y, paint.color_at(&p), coverage);
+                }
+            }
+        }
+    }
+}
+
+/// Non horizontal edge, top to bottom.
+struct Edge {
+    top: f32,
+    bottom: f32,
+    /// x at `top`
+    x: f32,
+    /// change of x per y
+    dx: f32,
+    winding: i32,
+}
+
+/// The scanline rasterizer every fill and stroke ends up in.
+/// Fills one or more closed outlines in screen coordinates, holes are outlines too.
+///
+/// Without anti aliasing a pixel is in if its center is. With it, every row is sampled on
+/// a few scanlines and each scanline adds the exact horizontal coverage of its spans.
+/// Every pixel is blended once, however many outlines overlap it.
+pub fn fill_contours(renderer: &mut Renderer, contours: &[Vec<VX2>], rule: FillRule, clr: u32) {
+    paint_contours(renderer, contours, rule, &Paint::solid(clr));
+}
+
+/// Like [`fill_contours`], colored by `paint`, which is in model coordinates.
+pub fn paint_contours(
+    renderer: &mut Renderer,
+    contours: &[Vec<VX2>],
+    rule: FillRule,
+    paint: &Paint,
+) {
+    let shade = match paint.as_solid() {
+        Some(c) => Shade::Solid(c.into()),
+        None => match renderer.projection().inverse() {
+            Some(to_model) => Shade::Paint(paint, to_model),
+            None => return,
+        },
+    };
+    let mut edges: Vec<Edge> = Vec::new();
+    for contour in contours.iter().filter(|c| c.len() > 2) {
+        let n = contour.len();
+        for i in 0..n {
+            let (a, b) = (contour[i], contour[(i + 1) % n]);
+            if a.y == b.y || !(a.y.is_finite() && b.y.is_finite()) {
+                continue;
+            }
+            let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+            edges.push(Edge {
+                top: top.y,
+                bottom: bottom.y,
+                x: top.x,
+                dx: (bottom.x - top.x) / (bottom.y - top.y),
+                winding,
+            });
+        }
+    }
+    if edges.is_empty() {
+        return;
+    }
+    edges.sort_by(|a, b| a.top.total_cmp(&b.top));
+
+    let (clip_x0, clip_y0, clip_x1, clip_y1) = renderer.clip_px();
+    let max_y = edges.iter().fold(f32::MIN, |m, e| m.max(e.bottom));
+    let y0 = (edges[0].top.floor() as i32).max(clip_y0);
+    let y1 = (max_y.ceil() as i32).min(clip_y1);
+    if y0 >= y1 || clip_x0 >= clip_x1 {
+        return;
+    }
+
+    let aa = renderer.anti_aliasing();
+    let samples = if aa { SUBSAMPLES } else { 1 };
+    let weight = 1.0 / samples as f32;
+    let width = (clip_x1 - clip_x0) as usize;
+    // partial coverage per pixel, and the start and end of fully covered runs
+    let mut partial = vec![0.0f32; width + 1];
+    let mut runs = vec![0.0f32; width + 1];
+    let mut next = 0;
+    let mut active: Vec<usize> = Vec::new();
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+    for y in y0..y1 {
+        let (mut row_x0, mut row_x1) = (width, 0);
+        for s in 0..samples {
+            let sy = y as f32 + (s as f32 + 0.5) * weight;
+            // top inclusive, bottom exclusive, so shared vertices count once
+            while next < edges.len() && edges[next].top <= sy {
+                active.push(next);
+                next += 1;
+            }
+            active.retain(|i| edges[*i].bottom > sy);
+            crossings.clear();
+            for e in active.iter().map(|i| &edges[*i]) {
+                crossings.push((e.x + (sy - e.top) * e.dx, e.winding));
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                let inside = match rule {
+                    FillRule::NonZero => winding != 0,
+                    FillRule::EvenOdd => winding % 2 != 0,
+                };
+                if !inside {
+                    continue;
+                }
+                let (from, to) = (pair[0].0 - clip_x0 as f32, pair[1].0 - clip_x0 as f32);
+                if aa {
+                    let from = from.clamp(0.0, width as f32);
+                    let to = to.clamp(0.0, width as f32);
+                    if to <= from {
+                        continue;
+                    }
+                    let (first, last) = (from as usize, (to as usize).min(width - 1));
+                    if first == last {
+                        partial[first] += (to - from) * weight;
+                    } else {
+                        partial[first] += (first as f32 + 1.0 - from) * weight;
+                        runs[first + 1] += weight;
+                        runs[last] -= weight;
+                        partial[last] += (to - last as f32) * weight;
+                    }
+                    row_x0 = row_x0.min(first);
+                    row_x1 = row_x1.max(last + 1);
+                } else {
+                    // pixel centers x + 0.5 in from..to
+                    let start = ((from - 0.5).ceil().max(0.0) as usize).min(width);
+                    let end = ((to - 0.5).ceil().max(0.0) as usize).min(width);
+                    if start < end {
+                        runs[start] += 1.0;
+                        runs[end] -= 1.0;
+                        row_x0 = row_x0.min(start);
+                        row_x1 = row_x1.max(end);
+                    }
+                }
+            }
+        }
+        if row_x0 < row_x1 {
+            blend_row(
+                renderer,
+                y,
+                clip_x0,
+                &mut partial[row_x0..=row_x1],
+                &mut runs[row_x0..=row_x1],
+                row_x0,
+                &shade,
+            );
+        }
+    }
+}
+
+/// Turns one row of accumulated coverage into spans and pixels, and resets it.
+fn blend_row(
+    renderer: &mut Renderer,
+    y: i32,
+    clip_x0: i32,
+    partial: &mut [f32],
+    runs: &mut [f32],
+    offset: usize,
+    shade: &Shade,
+) {
+    let x_of = |i: usize| clip_x0 + (offset + i) as i32;
+    let mut full = 0.0;
+    let mut span_start: Option<usize> = None;
+    for i in 0..partial.len() {
+        full += runs[i];
+        let coverage = (full + partial[i]).min(1.0);
+        runs[i] = 0.0;
+        partial[i] = 0.0;
+        if coverage >= 1.0 - 1e-4 {
+            span_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = span_start.take() {
+            shade.blend(renderer, x_of(start), x_of(i), y, 1.0);
+        }
+        if coverage > 1e-4 {
+            shade.blend(renderer, x_of(i), x_of(i) + 1, y, coverage);
+        }
+    }
+    if let Some(start) = span_start {
+        shade.blend(renderer, x_of(start), x_of(partial.len()), y, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::{polygon::FillRule, raster::fill_contours},
+        renderer::two_d::Renderer,
+        vx2,
+    };
+
+    #[test]
+    fn exact_coverage() {
+        let mut renderer = Renderer::new(8, 8);
+        renderer.clear_background(0xFF000000u32);
+        renderer.set_anti_aliasing(true);
+        // half a pixel off the grid on every side
+        let square = vec![
+            vx2!(1.5, 1.5),
+            vx2!(5.5, 1.5),
+            vx2!(5.5, 5.5),
+            vx2!(1.5, 5.5),
+        ];
+        fill_contours(&mut renderer, &[square], FillRule::NonZero, 0xFFFFFFFFu32);
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 8 + x] & 0xFF;
+        assert_eq!(px(3, 3), 0xFF);
+        assert_eq!(px(1, 3), 0x80);
+        assert_eq!(px(5, 3), 0x80);
+        assert_eq!(px(1, 1), 0x40);
+        assert_eq!(px(6, 6), 0);
+    }
+}
@@ -1,10 +1,11 @@
 use std::f32;
 
 use crate::{
-    colors::color::Color,
+    colors::{color::Color, paint::Paint},
     constants::DEFAULT_FOREGROUND,
     linal::vertx2::VX2,
     renderer::two_d::{Render, Renderer},
+    textures::{Texture, blit::Blit},
     vx2,
 };
 
@@ -14,8 +15,9 @@ use super::{
         epa::{EpaResult, epa},
         gjk::{furthest_polygon, gjk_for_epa},
     },
-    line::bresenham,
-    tri::Tri2d,
+    polygon::FillRule,
+    raster::paint_contours,
+    stroke::stroke_polyline,
     utils::{BBox2d, max_of_n, min_of_n},
 };
 
@@ -73,59 +75,37 @@ impl Rectangle {
 }
 impl Render for Rectangle {
     fn draw(&self, renderer: &mut Renderer) {
-        let [tl, tr, br, bl] = self.vertices_arr();
-        bresenham(renderer.buffer_mut(), &tl, &tr, self.stroke.into());
-        bresenham(renderer.buffer_mut(), &tr, &br, self.stroke.into());
-        bresenham(renderer.buffer_mut(), &br, &bl, self.stroke.into());
-        bresenham(renderer.buffer_mut(), &bl, &tl, self.stroke.into());
+        self.draw_clr(renderer, self.stroke);
     }
 
     fn fill(&self, renderer: &mut Renderer) {
-        let [tl, tr, br, bl] = self.vertices_arr();
-        Tri2d::new(tl, tr, bl).fill(renderer);
-        Tri2d::new(bl, tr, br).fill(renderer);
+        self.fill_clr(renderer, self.fill);
     }
     fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        let [tl, tr, br, bl] = self.vertices_arr();
-        Tri2d::new(tl, tr, bl).fill_clr(renderer, c);
-        Tri2d::new(bl, tr, br).fill_clr(renderer, c);
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        // one pass, two triangles would blend the diagonal twice
+        let verts = self.vertices_arr().map(|v| renderer.to_screen(&v));
+        paint_contours(renderer, &[verts.to_vec()], FillRule::NonZero, paint);
     }
     fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        let [tl, tr, br, bl] = self.vertices_arr();
-        bresenham(renderer.buffer_mut(), &tl, &tr, c.into());
-        bresenham(renderer.buffer_mut(), &tr, &br, c.into());
-        bresenham(renderer.buffer_mut(), &br, &bl, c.into());
-        bresenham(renderer.buffer_mut(), &bl, &tl, c.into());
+        let verts = self.vertices_arr().map(|v| renderer.to_screen(&v));
+        stroke_polyline(renderer, &verts, true, c.into());
     }
 
-    fn with_texture(&self, renderer: &mut Renderer, texture: &crate::textures::Texture) {
-        if self.rotation > f32::EPSILON {
-            panic!("Texture rendering on rectangle with rotation is not yet implemented");
-        }
-
-        let bbox = self.bbox();
-        let start_y = bbox.min_y as i32;
-        let end_y = bbox.max_y as i32;
-
-        let start_x = bbox.min_x as i32;
-        let width = self.size.x as usize;
-        if width != texture.size().x as usize {
-            panic!(
-                "At the moment we need texture to be the same size as the rectangle for this to work"
-            );
-        }
-
-        let mut texture_y = 0;
-        let tex = texture.get_buffer().get_ptr();
-        unsafe {
-            for y in start_y..end_y {
-                let dst = renderer.buffer_mut().get_xy(start_x, y).unwrap();
-
-                let src = tex.add(texture_y as usize * width);
-                texture_y += 1;
-                std::ptr::copy_nonoverlapping(src, dst, width);
-            }
+    /// Stretches the whole texture over the rectangle, rotation included.
+    fn with_texture(&self, renderer: &mut Renderer, texture: &Texture) {
+        let tex = texture.size();
+        if tex.x <= 0.0 || tex.y <= 0.0 {
+            return;
         }
+        let blit = Blit::new(self.pos)
+            .with_origin(0.5, 0.5)
+            .with_scale(self.size.x / tex.x, self.size.y / tex.y)
+            .with_rotation(self.rotation);
+        renderer.blit(texture, &blit);
     }
 }
 
@@ -0,0 +1,148 @@
+use crate::{
+    colors::{color::Color, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    linal::vertx2::VX2,
+    renderer::two_d::{Render, Renderer},
+    vx2,
+};
+
+use super::{
+    flatten,
+    polygon::{fill_outline, model_tolerance, stroke_outline},
+};
+
+/// Rectangle with rounded corners, positioned and rotated like [`super::rectangle::Rectangle`].
+#[derive(Debug, Clone, Copy)]
+pub struct RoundedRect {
+    /// center
+    pub pos: VX2,
+    pub size: VX2,
+    /// top left, top right, bottom right, bottom left
+    pub radii: [f32; 4],
+    pub rotation: f32,
+    stroke: Color,
+    fill: Color,
+}
+
+impl RoundedRect {
+    pub fn new(pos: VX2, size: VX2, radius: f32) -> Self {
+        Self::with_radii(pos, size, [radius; 4])
+    }
+    pub fn with_radii(pos: VX2, size: VX2, radii: [f32; 4]) -> Self {
+        Self {
+            pos,
+            size,
+            radii,
+            rotation: 0.0,
+            stroke: DEFAULT_FOREGROUND.into(),
+            fill: DEFAULT_FOREGROUND.into(),
+        }
+    }
+    pub fn set_rotation(&mut self, deg: f32) {
+        self.rotation = deg;
+    }
+    pub fn set_stroke<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.stroke = c.into();
+    }
+    pub fn set_fill<C: Into<Color> + Copy>(&mut self, c: C) {
+        self.fill = c.into();
+    }
+
+    /// Radii shrunk evenly until corners on the same side don't overlap.
+    pub fn fitted_radii(&self) -> [f32; 4] {
+        let [tl, tr, br, bl] = self.radii.map(|r| r.max(0.0));
+        let (w, h) = (self.size.x.abs(), self.size.y.abs());
+        let sides = [(tl + tr, w), (bl + br, w), (tl + bl, h), (tr + br, h)];
+        let scale = sides
+            .iter()
+            .filter(|(sum, _)| *sum > 0.0)
+            .fold(1.0f32, |s, (sum, len)| s.min(len / sum));
+        [tl, tr, br, bl].map(|r| r * scale)
+    }
+
+    /// The outline as a closed polyline, clockwise from the top left corner.
+    pub fn points(&self, tolerance: f32) -> Vec<VX2> {
+        let [tl, tr, br, bl] = self.fitted_radii();
+        let (hw, hh) = (self.size.x * 0.5, self.size.y * 0.5);
+        // corner centers and the angle each corner arc starts at
+        let corners = [
+            (vx2!(-hw + tl, -hh + tl), tl, 180.0),
+            (vx2!(hw - tr, -hh + tr), tr, 270.0),
+            (vx2!(hw - br, hh - br), br, 0.0),
+            (vx2!(-hw + bl, hh - bl), bl, 90.0),
+        ];
+        let mut local = Vec::new();
+        for (center, r, start) in corners {
+            if r > 0.0 {
+                flatten::arc(
+                    &mut local,
+                    &center,
+                    &vx2!(r, r),
+                    0.0,
+                    start,
+                    90.0,
+                    tolerance,
+                );
+            } else {
+                local.push(center);
+            }
+        }
+        let (s, c) = self.rotation.to_radians().sin_cos();
+        local
+            .iter()
+            .map(|p| {
+                vx2!(
+                    p.x * c - p.y * s + self.pos.x,
+                    p.x * s + p.y * c + self.pos.y
+                )
+            })
+            .collect()
+    }
+}
+
+impl Render for RoundedRect {
+    fn draw(&self, renderer: &mut Renderer) {
+        self.draw_clr(renderer, self.stroke);
+    }
+    fn fill(&self, renderer: &mut Renderer) {
+        self.fill_clr(renderer, self.fill);
+    }
+    fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let points = self.points(model_tolerance(renderer));
+        stroke_outline(renderer, &points, true, c.into());
+    }
+    fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
+        let clr: u32 = c.into();
+        self.fill_paint(renderer, &Paint::solid(clr));
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let points = self.points(model_tolerance(renderer));
+        fill_outline(renderer, &points, paint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::rounded_rect::RoundedRect,
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    #[test]
+    fn per_corner_radii() {
+        let mut renderer = Renderer::new(32, 32);
+        let rect =
+            RoundedRect::with_radii(vx2!(16.0, 16.0), vx2!(20.0, 20.0), [8.0, 0.0, 30.0, 0.0]);
+        // bottom right would eat the whole side, both get scaled down to fit
+        let [tl, _, br, _] = rect.fitted_radii();
+        assert!((br - 20.0).abs() < 1e-4 && (tl - 16.0 / 3.0).abs() < 1e-4);
+        rect.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 32 + x];
+        assert_eq!(px(6, 6), 0);
+        assert_ne!(px(25, 6), 0);
+        assert_ne!(px(6, 25), 0);
+        assert_eq!(px(25, 25), 0);
+        assert_ne!(px(16, 16), 0);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::{linal::vertx2::VX2, renderer::two_d::Renderer, vx2};
+
+use super::{
+    flatten::{self, DEFAULT_TOLERANCE},
+    line::draw_line,
+    polygon::FillRule,
+    raster::fill_contours,
+    tri::winding,
+};
+
+const DEFAULT_MITER_LIMIT: f32 = 4.0;
+
+/// How open ends of a stroke look.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LineCap {
+    /// ends exactly at the end point
+    #[default]
+    Butt,
+    /// half circle around the end point
+    Round,
+    /// goes half the width past the end point
+    Square,
+}
+
+/// How two segments of a stroke meet.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LineJoin {
+    /// sharp corner, falls back to bevel past the miter limit
+    #[default]
+    Miter,
+    Round,
+    /// corner cut off straight
+    Bevel,
+}
+
+/// Outline settings of the renderer, width and dash lengths are in model units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// longest miter, as a multiple of half the width
+    pub miter_limit: f32,
+    /// alternating dash and gap lengths, empty for a solid line
+    pub dash: Vec<f32>,
+    /// how far into the dash pattern the stroke starts
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: DEFAULT_MITER_LIMIT,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            ..Default::default()
+        }
+    }
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        self.miter_limit = limit;
+        self
+    }
+    pub fn with_dash(mut self, dash: &[f32], offset: f32) -> Self {
+        self.dash = dash.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+    fn is_dashed(&self) -> bool {
+        self.dash.iter().sum::<f32>() > 0.0 && self.dash.iter().all(|d| *d >= 0.0)
+    }
+}
+
+/// Outline through `points` (model space) with the renderer's stroke style.
+pub fn draw_polyline(renderer: &mut Renderer, points: &[VX2], closed: bool, clr: u32) {
+    let points: Vec<VX2> = points.iter().map(|p| renderer.to_screen(p)).collect();
+    stroke_polyline(renderer, &points, closed, clr);
+}
+
+/// Outline through `points` in screen coordinates with the renderer's stroke style.
+pub fn stroke_polyline(renderer: &mut Renderer, points: &[VX2], closed: bool, clr: u32) {
+    let hairline = renderer.to_screen_scale(renderer.stroke_style().width) <= 1.0;
+    if hairline && points.len() == 2 && !renderer.stroke_style().is_dashed() {
+        draw_line(renderer, &points[0], &points[1], clr);
+        return;
+    }
+    stroke_polylines(renderer, &[(points, closed)], clr);
+}
+
+/// Outlines of several polylines (screen coordinates, closed or not) in one go.
+/// Segments, joins and caps turn into outlines that go through the scanline rasterizer
+/// together, so overlaps don't get blended twice. Hairlines are drawn one pixel wide.
+pub fn stroke_polylines(renderer: &mut Renderer, lines: &[(&[VX2], bool)], clr: u32) {
+    let scale = renderer.to_screen_scale(1.0);
+    let style = renderer.stroke_style().clone();
+    if style.width <= 0.0 || scale <= 0.0 {
+        return;
+    }
+    let width = (style.width * scale).max(1.0);
+    let mut contours = Vec::new();
+    for (points, closed) in lines.iter().filter(|l| l.0.len() > 1) {
+        if style.is_dashed() {
+            let pattern: Vec<f32> = style.dash.iter().map(|d| d * scale).collect();
+            for dash in dashes(points, *closed, &pattern, style.dash_offset * scale) {
+                outline(&mut contours, &dash, false, width, &style);
+            }
+        } else {
+            outline(&mut contours, points, *closed, width, &style);
+        }
+    }
+    fill_contours(renderer, &contours, FillRule::NonZero, clr);
+}
+
+/// Shortest a dash pattern gets in pixels, shorter ones are stretched to it.
+const MIN_DASH_CYCLE: f32 = 1.0;
+/// Most times a dash pattern repeats along one polyline, it gets stretched to stay below.
+const MAX_DASH_CYCLES: f32 = 10_000.0;
+
+/// Splits the polyline into the "on" parts of the dash pattern.
+fn dashes(points: &[VX2], closed: bool, pattern: &[f32], offset: f32) -> Vec<Vec<VX2>> {
+    let mut path: Vec<VX2> = points.to_vec();
+    if closed {
+        path.push(points[0]);
+    }
+    // odd patterns repeat twice so dashes and gaps alternate
+    let mut pattern: Vec<f32> = if pattern.len() % 2 == 1 {
+        pattern.iter().chain(pattern.iter()).copied().collect()
+    } else {
+        pattern.to_vec()
+    };
+    let mut offset = offset;
+    // a tiny pattern on a long line would make millions of dashes
+    let length: f32 = path.windows(2).map(|s| (s[1] - &s[0]).length()).sum();
+    let shortest = MIN_DASH_CYCLE.max(length / MAX_DASH_CYCLES);
+    let mut total: f32 = pattern.iter().sum();
+    if total < shortest {
+        let stretch = shortest / total;
+        pattern.iter_mut().for_each(|d| *d *= stretch);
+        offset *= stretch;
+        total = shortest;
+    }
+    let mut idx = 0;
+    let mut left = offset.rem_euclid(total);
+    while left >= pattern[idx] {
+        left -= pattern[idx];
+        idx = (idx + 1) % pattern.len();
+    }
+    let mut left = pattern[idx] - left;
+
+    let mut out = Vec::new();
+    let mut current: Vec<VX2> = if idx % 2 == 0 {
+        vec![path[0]]
+    } else {
+        Vec::new()
+    };
+    for seg in path.windows(2) {
+        let (a, b) = (seg[0], seg[1]);
+        let len = (b - &a).length();
+        let mut pos = 0.0;
+        while len - pos > left {
+            pos += left;
+            let p = a + &((b - &a) * (pos / len));
+            if idx % 2 == 0 {
+                current.push(p);
+                out.push(std::mem::take(&mut current));
+            } else {
+                current.push(p);
+            }
+            idx = (idx + 1) % pattern.len();
+            left = pattern[idx];
+        }
+        left -= len - pos;
+        if idx % 2 == 0 {
+            current.push(b);
+        }
+    }
+    if current.len() > 1 {
+        out.push(current);
+    }
+    out
+}
+
+/// Outlines covering the stroke of one polyline.
+fn outline(
+    contours: &mut Vec<Vec<VX2>>,
+    points: &[VX2],
+    closed: bool,
+    width: f32,
+    style: &StrokeStyle,
+) {
+    let hw = width * 0.5;
+    // drop repeated points, they have no direction
+    let mut pts: Vec<VX2> = Vec::with_capacity(points.len());
+    for p in points {
+        if pts
+            .last()
+            .is_none_or(|l: &VX2| (*p - l).length() > f32::EPSILON)
+        {
+            pts.push(*p);
+        }
+    }
+    if closed && pts.len() > 2 && (pts[0] - &pts[pts.len() - 1]).length() <= f32::EPSILON {
+        pts.pop();
+    }
+    if pts.len() < 2 {
+        if style.cap == LineCap::Round && !points.is_empty() {
+            push_disc(contours, &points[0], hw);
+        }
+        return;
+    }
+    let closed = closed && pts.len() > 2;
+    let n = pts.len();
+    let segments = if closed { n } else { n - 1 };
+
+    for i in 0..segments {
+        let (mut a, mut b) = (pts[i], pts[(i + 1) % n]);
+        let d = direction(&a, &b);
+        if !closed && style.cap == LineCap::Square {
+            if i == 0 {
+                a -= &(d * hw);
+            }
+            if i == segments - 1 {
+                b += &(d * hw);
+            }
+        }
+        let nrm = vx2!(-d.y, d.x) * hw;
+        push_convex(contours, &[a + &nrm, b + &nrm, b - &nrm, a - &nrm]);
+    }
+
+    if !closed && style.cap == LineCap::Round {
+        push_disc(contours, &pts[0], hw);
+        push_disc(contours, &pts[n - 1], hw);
+    }
+
+    let joins = if closed { 0..n } else { 1..n - 1 };
+    for i in joins {
+        let v = pts[i];
+        let d1 = direction(&pts[(i + n - 1) % n], &v);
+        let d2 = direction(&v, &pts[(i + 1) % n]);
+        add_join(contours, &v, &d1, &d2, hw, style);
+    }
+}
+
+fn add_join(
+    contours: &mut Vec<Vec<VX2>>,
+    v: &VX2,
+    d1: &VX2,
+    d2: &VX2,
+    hw: f32,
+    style: &StrokeStyle,
+) {
+    let cross = d1.x * d2.y - d1.y * d2.x;
+    if cross.abs() < f32::EPSILON && d1.dot(d2) > 0.0 {
+        return;
+    }
+    if style.join == LineJoin::Round {
+        push_disc(contours, v, hw);
+        return;
+    }
+    // the gap opens on the side away from the turn
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let n1 = vx2!(-d1.y, d1.x) * (hw * side);
+    let n2 = vx2!(-d2.y, d2.x) * (hw * side);
+    let (o1, o2) = (*v + &n1, *v + &n2);
+
+    if style.join == LineJoin::Miter {
+        let mid = n1 + &n2;
+        let cos_half = mid.length() / (2.0 * hw);
+        if cos_half > f32::EPSILON && 1.0 / cos_half <= style.miter_limit {
+            let tip = *v + &(mid.normalized_clone() * (hw / cos_half));
+            push_convex(contours, &[*v, o1, tip, o2]);
+            return;
+        }
+    }
+    push_convex(contours, &[*v, o1, o2]);
+}
+
+fn direction(a: &VX2, b: &VX2) -> VX2 {
+    (*b - a).normalized_clone()
+}
+
+/// Adds a convex outline turned clockwise, so non-zero filling merges it with the rest.
+fn push_convex(contours: &mut Vec<Vec<VX2>>, verts: &[VX2]) {
+    match winding(verts) {
+        Some(sign) if sign > 0.0 => contours.push(verts.to_vec()),
+        Some(_) => contours.push(verts.iter().rev().copied().collect()),
+        None => {}
+    }
+}
+
+fn push_disc(contours: &mut Vec<Vec<VX2>>, center: &VX2, r: f32) {
+    let mut disc = Vec::new();
+    flatten::arc(
+        &mut disc,
+        center,
+        &vx2!(r, r),
+        0.0,
+        0.0,
+        360.0,
+        DEFAULT_TOLERANCE,
+    );
+    disc.pop();
+    push_convex(contours, &disc);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::{
+            line::Line2d,
+            rectangle::Rectangle,
+            stroke::{LineCap, LineJoin, StrokeStyle, dashes},
+        },
+        renderer::two_d::{Render, Renderer},
+        vx2,
+    };
+
+    fn drawn(renderer: &Renderer) -> Vec<(i32, i32)> {
+        let w = renderer.window_width();
+        let px = renderer.buffer().as_slice();
+        (0..px.len())
+            .filter(|i| px[*i] != 0)
+            .map(|i| ((i % w) as i32, (i / w) as i32))
+            .collect()
+    }
+
+    #[test]
+    fn caps_and_dashes() {
+        let mut renderer = Renderer::new(32, 16);
+        renderer.set_stroke_style(StrokeStyle::new(4.0));
+        let line = Line2d::new(vx2!(8.0, 8.0), vx2!(24.0, 8.0));
+        line.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        let px = drawn(&renderer);
+        assert_eq!(px.len(), 16 * 4);
+        assert!(
+            px.iter()
+                .all(|(x, y)| (8..24).contains(x) && (6..10).contains(y))
+        );
+
+        renderer.clear_background(0u32);
+        renderer.set_stroke_style(StrokeStyle::new(4.0).with_cap(LineCap::Square));
+        line.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_eq!(drawn(&renderer).len(), 20 * 4);
+
+        // 4 on, 4 off, starting 2 into the first dash
+        renderer.clear_background(0u32);
+        renderer.set_stroke_style(StrokeStyle::new(2.0).with_dash(&[4.0], 2.0));
+        line.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        let cols: Vec<i32> = drawn(&renderer)
+            .iter()
+            .filter(|p| p.1 == 8)
+            .map(|p| p.0)
+            .collect();
+        assert_eq!(cols, vec![8, 9, 14, 15, 16, 17, 22, 23]);
+
+        // patterns too small to see get stretched, to a pixel or to 10000 repeats a line
+        let short = [vx2!(0.0, 0.0), vx2!(16.0, 0.0)];
+        assert_eq!(dashes(&short, false, &[0.1, 0.1], 0.0).len(), 16);
+        let long = [vx2!(0.0, 0.0), vx2!(1e6, 0.0), vx2!(1e6, 1e6)];
+        // rounding can leave a sliver of one more at the end
+        let n = dashes(&long, true, &[1e-6], 0.0).len();
+        assert!((10_000..=10_001).contains(&n), "{n}");
+    }
+
+    #[test]
+    fn outlines_blend_once() {
+        let mut renderer = Renderer::new(16, 16);
+        renderer.clear_background(0xFF000000u32);
+        renderer.set_stroke_style(StrokeStyle::new(3.0).with_join(LineJoin::Round));
+        let rect = Rectangle::new(vx2!(8.0, 8.0), vx2!(10.0, 10.0));
+        rect.draw_clr(&mut renderer, 0x80FFFFFFu32);
+        let px = renderer.buffer().as_slice();
+        assert!(px.iter().all(|p| *p == 0xFF000000 || *p == 0xFF808080));
+        // corners and middle of the edges are covered, the inside is not
+        assert_eq!(px[3 * 16 + 3], 0xFF808080);
+        assert_eq!(px[13 * 16 + 8], 0xFF808080);
+        assert_eq!(px[8 * 16 + 8], 0xFF000000);
+        assert_eq!(px[16 + 1], 0xFF000000);
+
+        // miter fills the outer corner, bevel cuts it
+        renderer.set_stroke_style(StrokeStyle::new(3.0));
+        rect.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_eq!(renderer.buffer().as_slice()[16 + 1], 0xFFFFFFFF);
+        renderer.clear_background(0xFF000000u32);
+        renderer.set_stroke_style(StrokeStyle::new(3.0).with_join(LineJoin::Bevel));
+        rect.draw_clr(&mut renderer, 0xFFFFFFFFu32);
+        assert_eq!(renderer.buffer().as_slice()[16 + 1], 0xFF000000);
+    }
+}
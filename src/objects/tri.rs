@@ -1,10 +1,9 @@
 use crate::{
-    buffer::Buffer,
-    colors::color::Color,
+    colors::{color::Color, paint::Paint},
     constants::DEFAULT_FOREGROUND,
     linal::vertx2::VX2,
     max, min,
-    objects::line::bresenham,
+    objects::stroke::stroke_polyline,
     renderer::two_d::{Render, Renderer},
     vx2,
 };
@@ -15,7 +14,9 @@ use super::{
         epa::{EpaResult, epa},
         gjk::{furthest_polygon, gjk_for_epa},
     },
-    utils::{BBox2d, Bounds, edge_2d},
+    polygon::FillRule,
+    raster::{fill_contours, paint_contours},
+    utils::{BBox2d, Bounds},
 };
 
 #[derive(Debug)]
@@ -68,6 +69,16 @@ impl Tri2d {
             fill: self.fill,
         }
     }
+
+    fn projected(&self, renderer: &Renderer) -> Self {
+        Self {
+            p0: renderer.to_screen(&self.p0),
+            p1: renderer.to_screen(&self.p1),
+            p2: renderer.to_screen(&self.p2),
+            stroke: self.stroke,
+            fill: self.fill,
+        }
+    }
 }
 
 impl BBox2d for Tri2d {
@@ -83,76 +94,58 @@ impl BBox2d for Tri2d {
 
 impl Render for Tri2d {
     fn draw(&self, renderer: &mut Renderer) {
-        bresenham(
-            &mut renderer.buffer_mut(),
-            &self.p0,
-            &self.p1,
-            self.stroke.into(),
-        );
-        bresenham(
-            &mut renderer.buffer_mut(),
-            &self.p0,
-            &self.p2,
-            self.stroke.into(),
-        );
-        bresenham(
-            &mut renderer.buffer_mut(),
-            &self.p1,
-            &self.p2,
-            self.stroke.into(),
-        );
+        self.draw_clr(renderer, self.stroke);
     }
     fn fill(&self, renderer: &mut Renderer) {
-        fill_tri2d(&mut renderer.buffer_mut(), self, self.stroke);
+        self.fill_clr(renderer, self.stroke);
     }
-    fn draw_renderer(&self, renderer: &mut Renderer) {
+    fn draw_renderer(&self, _renderer: &mut Renderer) {
         unimplemented!("Default unimplemented for draw_renderer");
     }
-    fn fill_rendderer(&self, renderer: &mut Renderer) {
+    fn fill_rendderer(&self, _renderer: &mut Renderer) {
         unimplemented!("Default unimplemented for fill_renderer");
     }
     fn draw_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        bresenham(&mut renderer.buffer_mut(), &self.p0, &self.p1, c.into());
-        bresenham(&mut renderer.buffer_mut(), &self.p0, &self.p2, c.into());
-        bresenham(&mut renderer.buffer_mut(), &self.p1, &self.p2, c.into());
+        let tri = self.projected(renderer);
+        stroke_polyline(renderer, &[tri.p0, tri.p1, tri.p2], true, c.into());
     }
     fn fill_clr<C: Into<u32> + Copy>(&self, renderer: &mut Renderer, c: C) {
-        fill_tri2d(&mut renderer.buffer_mut(), self, c);
+        let tri = self.projected(renderer);
+        fill_tri2d(renderer, &tri, c);
+    }
+    fn fill_paint(&self, renderer: &mut Renderer, paint: &Paint) {
+        let tri = self.projected(renderer);
+        paint_contours(
+            renderer,
+            &[vec![tri.p0, tri.p1, tri.p2]],
+            FillRule::NonZero,
+            paint,
+        );
     }
 }
 
-pub fn fill_tri2d<C: Into<u32> + Copy>(buffer: &mut Buffer<u32>, tri: &Tri2d, c: C) {
-    let bbox = tri.bbox();
-    let area = edge_2d(&tri.p0, &tri.p1, &tri.p2);
-    if area == 0.0 {
-        return;
-    }
-    let (min_x, max_x, min_y, max_y) = (
-        bbox.min_x as i32,
-        bbox.max_x as i32,
-        bbox.min_y as i32,
-        bbox.max_y as i32,
+pub fn fill_tri2d<C: Into<u32> + Copy>(renderer: &mut Renderer, tri: &Tri2d, c: C) {
+    fill_contours(
+        renderer,
+        &[vec![tri.p0, tri.p1, tri.p2]],
+        FillRule::NonZero,
+        c.into(),
     );
+}
 
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let px = x as f32 + 0.5;
-            let py = y as f32 + 0.5;
-            let p = vx2!(px, py);
-
-            let w0 = edge_2d(&tri.p1, &tri.p2, &p);
-            let w1 = edge_2d(&tri.p2, &tri.p0, &p);
-            let w2 = edge_2d(&tri.p0, &tri.p1, &p);
-            let inside = if area > 0.0 {
-                (w0 >= 0.0) && (w1 >= 0.0) && (w2 >= 0.0)
-            } else {
-                (w0 <= 0.0) && (w1 <= 0.0) && (w2 <= 0.0)
-            };
-            if inside {
-                buffer.set_xy(x, y, c.into());
-            }
-        }
+/// Sign that makes `edge_2d` positive on the inside of `verts`, None if there is no area.
+pub(crate) fn winding(verts: &[VX2]) -> Option<f32> {
+    let n = verts.len();
+    let area: f32 = (0..n)
+        .map(|i| {
+            let (a, b) = (&verts[i], &verts[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    if n < 3 || area == 0.0 {
+        return None;
     }
+    Some(area.signum())
 }
 
 impl Vertices for Tri2d {
@@ -24,6 +24,7 @@ pub fn max_of_n(d: &[f32]) -> Option<f32> {
     })
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Bounds {
     pub min_x: f32,
     pub max_x: f32,
@@ -31,6 +32,41 @@ pub struct Bounds {
     pub max_y: f32,
 }
 
+impl Bounds {
+    /// From top left corner and size.
+    pub fn from_rect(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self {
+            min_x: x,
+            max_x: x + w,
+            min_y: y,
+            max_y: y + h,
+        }
+    }
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+    pub fn height(&self) -> f32 {
+        self.max_y - self.min_y
+    }
+    pub fn is_empty(&self) -> bool {
+        self.max_x <= self.min_x || self.max_y <= self.min_y
+    }
+    pub fn contains(&self, p: &VX2) -> bool {
+        p.x >= self.min_x && p.x < self.max_x && p.y >= self.min_y && p.y < self.max_y
+    }
+    /// Overlap of both, empty (zero sized) if they don't overlap.
+    pub fn intersect(&self, other: &Bounds) -> Bounds {
+        let min_x = self.min_x.max(other.min_x);
+        let min_y = self.min_y.max(other.min_y);
+        Bounds {
+            min_x,
+            max_x: self.max_x.min(other.max_x).max(min_x),
+            min_y,
+            max_y: self.max_y.min(other.max_y).max(min_y),
+        }
+    }
+}
+
 pub fn edge_2d(a: &VX2, b: &VX2, c: &VX2) -> f32 {
     (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
 }
@@ -1,6 +1,19 @@
 use std::u32;
 
-use crate::{buffer::Buffer, colors::color::Color, constants::DEFAULT_FOREGROUND, textures::Texture};
+use crate::{
+    buffer::Buffer,
+    colors::{color::Color, modify::BlendMode, paint::Paint},
+    constants::DEFAULT_FOREGROUND,
+    engine::camera_2d::Camera2dRef,
+    fonts::{TextFont, layout::TextStyle},
+    linal::{mat3::Mat3, vertx2::VX2},
+    objects::{stroke::StrokeStyle, utils::Bounds},
+    textures::{
+        Texture,
+        blit::{Blit, sample},
+    },
+    vx2,
+};
 
 pub trait Render {
     fn draw(&self, _renderer: &mut Renderer) {
@@ -21,16 +34,55 @@ pub trait Render {
     fn fill_clr<C: Into<u32> + Copy>(&self, _renderer: &mut Renderer, _c: C) {
         unimplemented!("Default unimplemented for fill_clr");
     }
+    /// Fill with a gradient or pattern, `fill_clr` is the solid case.
+    fn fill_paint(&self, _renderer: &mut Renderer, _paint: &Paint) {
+        unimplemented!("Default unimplemented for fill_paint");
+    }
     fn with_texture(&self, _renderer: &mut Renderer, _texture: &Texture) {
         unimplemented!("Default unimplemented for texure");
     } 
+    /// `draw` with anti aliasing switched on or off for this call only.
+    fn draw_aa(&self, renderer: &mut Renderer, aa: bool) {
+        let prev = renderer.anti_aliasing();
+        renderer.set_anti_aliasing(aa);
+        self.draw(renderer);
+        renderer.set_anti_aliasing(prev);
+    }
+    /// `fill` with anti aliasing switched on or off for this call only.
+    fn fill_aa(&self, renderer: &mut Renderer, aa: bool) {
+        let prev = renderer.anti_aliasing();
+        renderer.set_anti_aliasing(aa);
+        self.fill(renderer);
+        renderer.set_anti_aliasing(prev);
+    }
+}
+
+/// What happens to the renderer buffer when the window changes size.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ResizePolicy {
+    /// buffer keeps its size and gets scaled to the whole window
+    Stretch,
+    /// buffer keeps its size and gets scaled with its aspect ratio, the rest stays black
+    Letterbox,
+    /// buffer is resized to the window size
+    #[default]
+    Follow,
 }
 
 pub struct Renderer {
     buffer: Buffer<u32>,
     fill: Color,
     stroke: Color,
+    stroke_style: StrokeStyle,
     anti_aliasing: bool,
+    blend_mode: BlendMode,
+    resize_policy: ResizePolicy,
+    camera: Option<Camera2dRef>,
+    transform: Mat3,
+    transform_stack: Vec<Mat3>,
+    clip_stack: Vec<Bounds>,
+    /// active clip in whole pixels, x0, y0, x1, y1 with x1 and y1 exclusive
+    clip_px: (i32, i32, i32, i32),
 }
 
 impl Renderer {
@@ -39,7 +91,15 @@ impl Renderer {
             buffer: Buffer::init(width as i32, height as i32),
             fill: DEFAULT_FOREGROUND.into(),
             stroke: DEFAULT_FOREGROUND.into(),
+            stroke_style: StrokeStyle::default(),
             anti_aliasing: false,
+            blend_mode: BlendMode::default(),
+            resize_policy: ResizePolicy::default(),
+            camera: None,
+            transform: Mat3::identity(),
+            transform_stack: Vec::new(),
+            clip_stack: Vec::new(),
+            clip_px: (0, 0, width as i32, height as i32),
         }
     }
     pub fn window_width(&self) -> usize {
@@ -49,6 +109,159 @@ impl Renderer {
         self.buffer.height() as usize
     }
 
+    pub fn set_anti_aliasing(&mut self, aa: bool) {
+        self.anti_aliasing = aa;
+    }
+    pub fn anti_aliasing(&self) -> bool {
+        self.anti_aliasing
+    }
+
+    /// Used by every fill and stroke from here on.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Blends one pixel in screen coordinates, `coverage` scales the color's alpha.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, clr: u32, coverage: f32) {
+        let (x0, y0, x1, y1) = self.clip_px;
+        if x < x0 || x >= x1 || y < y0 || y >= y1 {
+            return;
+        }
+        self.buffer.blend_xy(x, y, clr, coverage, self.blend_mode);
+    }
+
+    /// Blends the pixels `x_start..x_end` of row `y`, screen coordinates.
+    pub fn blend_span(&mut self, y: i32, x_start: i32, x_end: i32, clr: u32) {
+        let (x0, y0, x1, y1) = self.clip_px;
+        if y < y0 || y >= y1 {
+            return;
+        }
+        let (start, end) = (x_start.max(x0), x_end.min(x1));
+        if start < end {
+            self.buffer.blend_span(y, start, end, clr, self.blend_mode);
+        }
+    }
+
+    /// Like `Buffer::scanlinef`, but clipped and blended.
+    pub fn scanlinef(&mut self, line: f32, x_start: f32, x_end: f32, clr: u32) {
+        self.blend_span(line as i32, x_start.ceil() as i32, x_end.floor() as i32, clr);
+    }
+
+    /// Limits drawing to `rect` in screen pixels, intersected with the clip already active.
+    pub fn push_clip(&mut self, rect: Bounds) {
+        let clip = self.clip().intersect(&rect);
+        self.clip_stack.push(clip);
+        self.update_clip();
+    }
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+        self.update_clip();
+    }
+    /// The area drawing is limited to, the whole buffer if no clip is pushed.
+    pub fn clip(&self) -> Bounds {
+        let (w, h) = (self.buffer.width() as f32, self.buffer.height() as f32);
+        let full = Bounds::from_rect(0.0, 0.0, w, h);
+        match self.clip_stack.last() {
+            Some(clip) => clip.intersect(&full),
+            None => full,
+        }
+    }
+    /// [`Renderer::clip`] in whole pixels: x0, y0, x1, y1, with x1 and y1 exclusive.
+    pub fn clip_px(&self) -> (i32, i32, i32, i32) {
+        self.clip_px
+    }
+    fn update_clip(&mut self) {
+        let clip = self.clip();
+        self.clip_px = (
+            clip.min_x.round() as i32,
+            clip.min_y.round() as i32,
+            clip.max_x.round() as i32,
+            clip.max_y.round() as i32,
+        );
+    }
+
+    pub fn set_resize_policy(&mut self, policy: ResizePolicy) {
+        self.resize_policy = policy;
+    }
+    pub fn resize_policy(&self) -> ResizePolicy {
+        self.resize_policy
+    }
+
+    /// Zero sized resizes, e.g. of a minimized window, are ignored.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.buffer.resize(width, height);
+        self.update_clip();
+    }
+
+    /// Everything drawn through [`Render`] goes through this camera.
+    pub fn set_camera(&mut self, camera: Option<Camera2dRef>) {
+        self.camera = camera;
+    }
+    pub fn camera(&self) -> Option<Camera2dRef> {
+        self.camera.clone()
+    }
+
+    /// Saves the current transform, restore it with [`Renderer::pop`].
+    pub fn push(&mut self) {
+        self.transform_stack.push(self.transform);
+    }
+    pub fn pop(&mut self) {
+        if let Some(t) = self.transform_stack.pop() {
+            self.transform = t;
+        }
+    }
+    /// Transforms are applied in local space, the last one called is applied first to a shape.
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.transform *= Mat3::translation(x, y);
+    }
+    pub fn rotate(&mut self, deg: f32) {
+        self.transform *= Mat3::rotation(deg);
+    }
+    pub fn scale(&mut self, sx: f32, sy: f32) {
+        self.transform *= Mat3::scale(sx, sy);
+    }
+    pub fn set_transform(&mut self, transform: Mat3) {
+        self.transform = transform;
+    }
+    pub fn reset_transform(&mut self) {
+        self.transform = Mat3::identity();
+    }
+    pub fn transform(&self) -> Mat3 {
+        self.transform
+    }
+
+    /// Current transform followed by the camera view.
+    pub fn projection(&self) -> Mat3 {
+        match &self.camera {
+            Some(cam) => cam.borrow().view_matrix() * self.transform,
+            None => self.transform,
+        }
+    }
+
+    /// Model to screen coordinates: current transform first, then the camera.
+    pub fn to_screen(&self, p: &VX2) -> VX2 {
+        let p = self.transform.transform_point(p);
+        match &self.camera {
+            Some(cam) => cam.borrow().world_to_screen(&p),
+            None => p,
+        }
+    }
+
+    /// Model length (e.g. a radius) to screen length.
+    pub fn to_screen_scale(&self, len: f32) -> f32 {
+        let len = len * self.transform.scale_factor();
+        match &self.camera {
+            Some(cam) => cam.borrow().world_to_screen_scale(len),
+            None => len,
+        }
+    }
+
     pub fn set_fill<C: Into<Color> + Copy>(&mut self, fill: C) {
         self.fill = fill.into();
     }
@@ -61,6 +274,16 @@ impl Renderer {
     pub fn stroke(&self) -> Color {
         self.stroke
     }
+    /// Width, caps, joins and dashes of every outline drawn from here on.
+    pub fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+    pub fn stroke_style(&self) -> &StrokeStyle {
+        &self.stroke_style
+    }
+    pub fn set_stroke_width(&mut self, width: f32) {
+        self.stroke_style.width = width;
+    }
 
     pub fn buffer<'r>(&'r self) -> &'r Buffer<u32> {
         &self.buffer
@@ -70,9 +293,312 @@ impl Renderer {
         &mut self.buffer
     }
 
+    /// Points the renderer at `texture` for the duration of `draw`, everything drawn in there
+    /// ends up in the texture. Transform, clip and camera start out empty and get restored after.
+    pub fn render_to<F: FnOnce(&mut Renderer)>(&mut self, texture: &mut Texture, draw: F) {
+        std::mem::swap(&mut self.buffer, texture.get_buffer_mut());
+        let transform = std::mem::take(&mut self.transform);
+        let transform_stack = std::mem::take(&mut self.transform_stack);
+        let clip_stack = std::mem::take(&mut self.clip_stack);
+        let camera = self.camera.take();
+        self.update_clip();
+
+        draw(self);
+
+        std::mem::swap(&mut self.buffer, texture.get_buffer_mut());
+        self.transform = transform;
+        self.transform_stack = transform_stack;
+        self.clip_stack = clip_stack;
+        self.camera = camera;
+        self.update_clip();
+    }
+
+    /// Draws `text` with `font`, `pos` is where the alignment of `style` puts it. Lines break
+    /// at `\n` and, with a max width, where `style.overflow` says. Clipped like everything else.
+    /// Works the same for outline and bitmap fonts.
+    pub fn draw_text<F: TextFont + ?Sized>(
+        &mut self,
+        font: &F,
+        text: &str,
+        pos: VX2,
+        style: &TextStyle,
+    ) {
+        font.draw(text, pos, style, self);
+    }
+
+    /// Composites `texture` with its top left corner at `pos`, scaled by `scale`, its alpha
+    /// multiplied by `alpha`. Shorthand for the common case of [`Renderer::blit`].
+    pub fn draw_texture(&mut self, texture: &Texture, pos: VX2, scale: f32, alpha: f32) {
+        let blit = Blit::new(pos).with_scale(scale, scale).with_alpha(alpha);
+        self.blit(texture, &blit);
+    }
+
+    /// Draws a textured quad as described by `blit`, clipped and blended with the current
+    /// blend mode. Goes through the transform and camera like every other shape.
+    pub fn blit(&mut self, texture: &Texture, blit: &Blit) {
+        let buffer = texture.get_buffer();
+        let full = Bounds::from_rect(0.0, 0.0, buffer.width() as f32, buffer.height() as f32);
+        let src = match blit.src {
+            // whole texels only, sampling can't pick from a fraction of one
+            Some(src) => {
+                let src = src.intersect(&full);
+                Bounds {
+                    min_x: src.min_x.floor(),
+                    max_x: src.max_x.ceil(),
+                    min_y: src.min_y.floor(),
+                    max_y: src.max_y.ceil(),
+                }
+            }
+            None => full,
+        };
+        if src.is_empty() || blit.alpha <= 0.0 {
+            return;
+        }
+        let (w, h) = (src.width(), src.height());
+
+        // texels of the source rect to screen pixels
+        let model = self.projection()
+            * Mat3::translation(blit.pos.x, blit.pos.y)
+            * Mat3::rotation(blit.rotation)
+            * Mat3::scale(blit.scale.x, blit.scale.y)
+            * Mat3::translation(-blit.origin.x * w, -blit.origin.y * h);
+        let Some(inv) = model.inverse() else {
+            return;
+        };
+
+        let corners = [vx2!(0.0, 0.0), vx2!(w, 0.0), vx2!(w, h), vx2!(0.0, h)]
+            .map(|c| model.transform_point(&c));
+        let (clip_x0, clip_y0, clip_x1, clip_y1) = self.clip_px;
+        let min_y = corners.iter().fold(f32::MAX, |m, c| m.min(c.y));
+        let max_y = corners.iter().fold(f32::MIN, |m, c| m.max(c.y));
+        let y0 = (min_y.floor() as i32).max(clip_y0);
+        let y1 = (max_y.ceil() as i32).min(clip_y1);
+
+        // u and v change linearly along a row: u = du * x + row_u
+        let [[du, _, _], [dv, _, _], _] = inv.m;
+        for y in y0..y1 {
+            let row = inv.transform_point(&vx2!(0.0, y as f32 + 0.5));
+            // pixel centers x + 0.5 that land inside 0..w and 0..h
+            let Some((from_u, to_u)) = span_inside(du, row.x, w) else {
+                continue;
+            };
+            let Some((from_v, to_v)) = span_inside(dv, row.y, h) else {
+                continue;
+            };
+            let x_start = ((from_u.max(from_v) - 0.5).ceil() as i32).max(clip_x0);
+            let x_end = ((to_u.min(to_v) - 0.5).ceil() as i32).min(clip_x1);
+            for x in x_start..x_end {
+                let px = x as f32 + 0.5;
+                let mut u = du * px + row.x;
+                let mut v = dv * px + row.y;
+                if blit.flip_h {
+                    u = w - u;
+                }
+                if blit.flip_v {
+                    v = h - v;
+                }
+                let clr = sample(buffer, &src, u, v, blit.sampling);
+                self.buffer.blend_xy(x, y, clr, blit.alpha, self.blend_mode);
+            }
+        }
+    }
+
     pub fn clear_background<C: Into<u32> + Copy>(&mut self, c: C) {
         for i in 0..self.buffer.len() {
             self.buffer.set(i, c.into());
         }
     }
 }
+
+/// Range of `x` for which `slope * x + offset` lies in `0.0..len`, None if empty.
+fn span_inside(slope: f32, offset: f32, len: f32) -> Option<(f32, f32)> {
+    if slope.abs() < f32::EPSILON {
+        return (0.0..len).contains(&offset).then_some((f32::MIN, f32::MAX));
+    }
+    let (a, b) = ((0.0 - offset) / slope, (len - offset) / slope);
+    let (from, to) = if a < b { (a, b) } else { (b, a) };
+    (from < to).then_some((from, to))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        colors::{
+            modify::BlendMode,
+            paint::{Gradient, Paint, Pattern},
+        },
+        objects::{line::Line2d, rectangle::Rectangle, tri::Tri2d, utils::Bounds},
+        renderer::two_d::{Render, Renderer},
+        textures::{
+            Texture,
+            blit::{Blit, Sampling},
+        },
+        vx2,
+    };
+
+    #[test]
+    fn transform_stack() {
+        let mut renderer = Renderer::new(16, 16);
+        renderer.translate(10.0, 0.0);
+        renderer.push();
+        renderer.rotate(90.0);
+        renderer.scale(2.0, 2.0);
+        let p = renderer.to_screen(&vx2!(1.0, 0.0));
+        assert!((p.x - 10.0).abs() < 0.001 && (p.y - 2.0).abs() < 0.001);
+        assert!((renderer.to_screen_scale(3.0) - 6.0).abs() < 0.001);
+        renderer.pop();
+        let p = renderer.to_screen(&vx2!(1.0, 0.0));
+        assert!((p.x - 11.0).abs() < 0.001 && p.y.abs() < 0.001);
+    }
+
+    #[test]
+    fn anti_aliased_fill() {
+        let mut renderer = Renderer::new(16, 16);
+        let tri = Tri2d::new(vx2!(0.0, 0.0), vx2!(16.0, 0.0), vx2!(0.0, 16.0));
+        renderer.clear_background(0xFF000000u32);
+        renderer.set_anti_aliasing(true);
+        tri.fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        renderer.set_anti_aliasing(false);
+        let px = |r: &Renderer, x: usize, y: usize| r.buffer().as_slice()[y * 16 + x];
+        // inside is solid, the diagonal edge is blended, outside is untouched
+        assert_eq!(px(&renderer, 2, 2), 0xFFFFFFFF);
+        let edge = px(&renderer, 7, 8) & 0xFF;
+        assert!(edge > 0 && edge < 0xFF, "{edge:x}");
+        assert_eq!(px(&renderer, 12, 12), 0xFF000000);
+    }
+
+    #[test]
+    fn translucent_fill() {
+        let mut renderer = Renderer::new(8, 8);
+        renderer.clear_background(0xFF000000u32);
+        let rect = Rectangle::new(vx2!(4.0, 4.0), vx2!(8.0, 8.0));
+        rect.fill_clr(&mut renderer, 0x80FFFFFFu32);
+        // every pixel blended exactly once, the diagonal included
+        assert!(renderer.buffer().as_slice().iter().all(|p| *p == 0xFF808080));
+        renderer.set_blend_mode(BlendMode::Replace);
+        rect.fill_clr(&mut renderer, 0x80FFFFFFu32);
+        assert_eq!(renderer.buffer().as_slice()[9], 0x80FFFFFF);
+    }
+
+    #[test]
+    fn nested_clip() {
+        let mut renderer = Renderer::new(16, 16);
+        renderer.push_clip(Bounds::from_rect(2.0, 2.0, 10.0, 10.0));
+        renderer.push_clip(Bounds::from_rect(8.0, 0.0, 100.0, 6.0));
+        assert_eq!(renderer.clip_px(), (8, 2, 12, 6));
+
+        Rectangle::new(vx2!(8.0, 8.0), vx2!(16.0, 16.0)).fill_clr(&mut renderer, 0xFFFFFFFFu32);
+        Line2d::new(vx2!(-20.0, 4.0), vx2!(40.0, 4.0)).draw_clr(&mut renderer, 0xFFFF0000u32);
+        let drawn: Vec<(usize, usize)> = (0..16 * 16)
+            .filter(|i| renderer.buffer().as_slice()[*i] != 0)
+            .map(|i| (i % 16, i / 16))
+            .collect();
+        assert_eq!(drawn.len(), 16);
+        assert!(drawn.iter().all(|(x, y)| (8..12).contains(x) && (2..6).contains(y)));
+
+        renderer.pop_clip();
+        assert_eq!(renderer.clip_px(), (2, 2, 12, 12));
+        renderer.pop_clip();
+        assert_eq!(renderer.clip_px(), (0, 0, 16, 16));
+    }
+
+    #[test]
+    fn render_target() {
+        let mut renderer = Renderer::new(16, 16);
+        renderer.clear_background(0xFF000000u32);
+        renderer.translate(100.0, 100.0);
+        renderer.push_clip(Bounds::from_rect(0.0, 0.0, 1.0, 1.0));
+
+        let mut texture = Texture::init(vx2!(4.0, 4.0));
+        renderer.render_to(&mut texture, |r| {
+            assert_eq!(r.clip_px(), (0, 0, 4, 4));
+            Rectangle::new(vx2!(1.0, 2.0), vx2!(2.0, 4.0)).fill_clr(r, 0xFFFFFFFFu32);
+        });
+        assert_eq!(renderer.clip_px(), (0, 0, 1, 1));
+        assert_eq!(texture.get_buffer().as_slice()[2], 0x00000000);
+        assert_eq!(texture.get_buffer().as_slice()[0], 0xFFFFFFFF);
+
+        renderer.pop_clip();
+        renderer.reset_transform();
+        renderer.draw_texture(&texture, vx2!(8.0, 8.0), 2.0, 0.5);
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 16 + x];
+        // 2x2 white block per texel at half alpha, transparent texels leave the background
+        assert_eq!(px(8, 8), 0xFF808080);
+        assert_eq!(px(11, 15), 0xFF808080);
+        assert_eq!(px(12, 8), 0xFF000000);
+        assert_eq!(px(7, 8), 0xFF000000);
+    }
+
+    #[test]
+    fn paint_fill() {
+        let mut renderer = Renderer::new(16, 4);
+        let rect = Rectangle::new(vx2!(8.0, 2.0), vx2!(16.0, 4.0));
+        let gradient = Gradient::two(0xFF000000u32, 0xFFFFFFFFu32);
+        rect.fill_paint(&mut renderer, &Paint::linear(vx2!(0.0, 0.0), vx2!(16.0, 0.0), gradient));
+        let px = |r: &Renderer, x: usize| r.buffer().as_slice()[x + 16] & 0xFF;
+        assert!((1..16).all(|x| px(&renderer, x) > px(&renderer, x - 1)));
+        // sampled at pixel centers
+        assert_eq!(px(&renderer, 0), 0x08);
+        assert_eq!(px(&renderer, 15), 0xF7);
+
+        // the paint moves with the transform, 2x2 checker with texels 2 wide
+        let mut checker = Texture::init(vx2!(2.0, 2.0));
+        for (i, clr) in [0xFFFFFFFF, 0xFF000000, 0xFF000000, 0xFFFFFFFF].iter().enumerate() {
+            checker.get_buffer_mut().set(i as i32, *clr);
+        }
+        renderer.translate(1.0, 0.0);
+        let pattern = Pattern::new(&checker).with_scale(2.0, 2.0);
+        rect.fill_paint(&mut renderer, &pattern.into());
+        let row: Vec<u32> = (0..10).map(|x| px(&renderer, x)).collect();
+        assert_eq!(row, vec![0x08, 0xFF, 0xFF, 0, 0, 0xFF, 0xFF, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn blit_sub_rect_flip_rotate() {
+        // 4x2 texture, left half red, right half blue
+        let mut texture = Texture::init(vx2!(4.0, 2.0));
+        for i in 0..8 {
+            let clr = if i % 4 < 2 { 0xFFFF0000 } else { 0xFF0000FF };
+            texture.get_buffer_mut().set(i, clr);
+        }
+        let mut renderer = Renderer::new(8, 8);
+        let px = |r: &Renderer, x: usize, y: usize| r.buffer().as_slice()[y * 8 + x];
+
+        let blit = Blit::new(vx2!(0.0, 0.0)).with_src(Bounds::from_rect(1.0, 0.0, 2.0, 2.0));
+        renderer.blit(&texture, &blit);
+        assert_eq!((px(&renderer, 0, 0), px(&renderer, 1, 1)), (0xFFFF0000, 0xFF0000FF));
+        assert_eq!(px(&renderer, 2, 0), 0);
+
+        renderer.clear_background(0u32);
+        renderer.blit(&texture, &blit.with_flip(true, false));
+        assert_eq!((px(&renderer, 0, 0), px(&renderer, 1, 1)), (0xFF0000FF, 0xFFFF0000));
+
+        // a quarter turn around the center turns the columns into rows
+        renderer.clear_background(0u32);
+        let rotated = Blit::new(vx2!(4.0, 4.0))
+            .with_origin(0.5, 0.5)
+            .with_rotation(90.0)
+            .with_scale(2.0, 2.0);
+        renderer.blit(&texture, &rotated);
+        assert_eq!((px(&renderer, 4, 1), px(&renderer, 4, 6)), (0xFFFF0000, 0xFF0000FF));
+        assert_eq!((px(&renderer, 1, 4), px(&renderer, 6, 4)), (0, 0));
+
+        // partly off screen and bilinear, just must not read or write out of bounds
+        let off = Blit::new(vx2!(-3.0, 6.0)).with_scale(3.5, 3.5).with_sampling(Sampling::Bilinear);
+        renderer.blit(&texture, &off);
+        Rectangle::new(vx2!(7.0, 7.0), vx2!(8.0, 2.0)).with_texture(&mut renderer, &texture);
+    }
+
+    #[test]
+    fn blit_sub_texel_src() {
+        let mut texture = Texture::init(vx2!(4.0, 2.0));
+        texture.get_buffer_mut().set(3, 0xFF0000FF);
+        let mut renderer = Renderer::new(4, 4);
+        // less than a texel wide, snaps out to the whole texel at 3, 0
+        let blit = Blit::new(vx2!(0.0, 0.0)).with_src(Bounds::from_rect(3.2, 0.0, 0.5, 1.0));
+        renderer.blit(&texture, &blit);
+        renderer.blit(&texture, &blit.with_sampling(Sampling::Bilinear).with_scale(2.0, 2.0));
+        let px = |x: usize, y: usize| renderer.buffer().as_slice()[y * 4 + x];
+        assert_eq!((px(0, 0), px(1, 1), px(2, 0)), (0xFF0000FF, 0xFF0000FF, 0));
+    }
+}
@@ -1,2 +1,2 @@
+pub mod scene;
 pub mod two_d;
-
@@ -0,0 +1,330 @@
+use std::marker::PhantomData;
+
+use crate::{
+    context::time::TimeInfo, engine::camera_2d::Camera2dRef, events::input::Events,
+    renderer::two_d::Renderer,
+};
+
+use super::two_d::State;
+
+/// Returned from [`Scene::update`] to change the scene stack.
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    Quit,
+}
+
+pub trait Scene {
+    /// Same purpose as `State::user_init`, called once the scene is on the stack.
+    fn init(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {}
+    /// Only the top scene gets updated.
+    fn update(
+        &mut self,
+        renderer: &mut Renderer,
+        events: &Events,
+        camera: Camera2dRef,
+        time_info: &TimeInfo,
+    ) -> Transition;
+    /// Used instead of `update` once the engine runs with a fixed timestep, only the top
+    /// scene gets it. The transition is applied before the next render.
+    fn fixed_update(&mut self, _events: &Events, _camera: Camera2dRef, _dt: f32) -> Transition {
+        Transition::None
+    }
+    /// Called after the update, for the top scene and every scene visible below it.
+    fn render(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {}
+    /// `render` with a fixed timestep, `alpha` is the same as in `State::render`.
+    /// Doesn't interpolate by default.
+    fn render_interpolated(&mut self, renderer: &mut Renderer, camera: Camera2dRef, _alpha: f32) {
+        self.render(renderer, camera);
+    }
+    /// An overlay lets the scene below it keep rendering, e.g. a pause menu.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+    fn resize(&mut self, _renderer: &mut Renderer, _width: usize, _height: usize) {}
+    /// Called when the scene gets popped or replaced.
+    fn on_exit(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {}
+}
+
+/// The scene a [`SceneStack`] starts with.
+pub trait RootScene: Scene + 'static {
+    fn create(renderer: &mut Renderer, camera: Camera2dRef) -> Self;
+}
+
+/// Implements [`State`] so it can be handed to the engine as is:
+/// `Engine::<SceneStack<MainMenu>>::create_window(..)`.
+/// The engine quits once the last scene is popped.
+pub struct SceneStack<R: RootScene> {
+    scenes: Vec<Box<dyn Scene>>,
+    /// returned by a fixed tick, waits for the renderer in `State::render`. No ticks run
+    /// until then, the scene they would go to might be gone.
+    pending: Option<Transition>,
+    _root: PhantomData<R>,
+}
+
+impl<R: RootScene> SceneStack<R> {
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Returns true if the stack is done, either because of `Quit` or because it is empty.
+    pub fn apply(
+        &mut self,
+        transition: Transition,
+        renderer: &mut Renderer,
+        camera: Camera2dRef,
+    ) -> bool {
+        match transition {
+            Transition::None => {}
+            Transition::Push(mut scene) => {
+                scene.init(renderer, camera);
+                self.scenes.push(scene);
+            }
+            Transition::Pop => {
+                if let Some(mut scene) = self.scenes.pop() {
+                    scene.on_exit(renderer, camera);
+                }
+            }
+            Transition::Replace(mut scene) => {
+                if let Some(mut old) = self.scenes.pop() {
+                    old.on_exit(renderer, camera.clone());
+                }
+                scene.init(renderer, camera);
+                self.scenes.push(scene);
+            }
+            Transition::Quit => {
+                while let Some(mut scene) = self.scenes.pop() {
+                    scene.on_exit(renderer, camera.clone());
+                }
+            }
+        }
+        self.scenes.is_empty()
+    }
+
+    /// `alpha` is only there with a fixed timestep.
+    fn render_scenes(&mut self, renderer: &mut Renderer, camera: Camera2dRef, alpha: Option<f32>) {
+        let mut bottom = self.scenes.len().saturating_sub(1);
+        while bottom > 0 && self.scenes[bottom].is_overlay() {
+            bottom -= 1;
+        }
+        for scene in self.scenes[bottom..].iter_mut() {
+            match alpha {
+                Some(alpha) => scene.render_interpolated(renderer, camera.clone(), alpha),
+                None => scene.render(renderer, camera.clone()),
+            }
+        }
+    }
+}
+
+impl<R: RootScene> State for SceneStack<R> {
+    fn user_init(renderer: &mut Renderer, camera: Camera2dRef) -> Self {
+        let mut root = R::create(renderer, camera.clone());
+        root.init(renderer, camera);
+        Self {
+            scenes: vec![Box::new(root)],
+            pending: None,
+            _root: PhantomData,
+        }
+    }
+
+    fn user_update(
+        &mut self,
+        renderer: &mut Renderer,
+        events: Events,
+        camera: Camera2dRef,
+        time_info: &TimeInfo,
+    ) -> bool {
+        let Some(top) = self.scenes.last_mut() else {
+            return true;
+        };
+        let transition = top.update(renderer, &events, camera.clone(), time_info);
+        if self.apply(transition, renderer, camera.clone()) {
+            return true;
+        }
+        self.render_scenes(renderer, camera, None);
+        false
+    }
+
+    /// Quits on the tick after the last scene is gone, popping needs the renderer.
+    fn fixed_update(&mut self, events: &Events, camera: Camera2dRef, dt: f32) -> bool {
+        if self.pending.is_some() {
+            return false;
+        }
+        let Some(top) = self.scenes.last_mut() else {
+            return true;
+        };
+        match top.fixed_update(events, camera, dt) {
+            Transition::None => {}
+            transition => self.pending = Some(transition),
+        }
+        false
+    }
+
+    fn render(&mut self, renderer: &mut Renderer, camera: Camera2dRef, alpha: f32) {
+        if let Some(transition) = self.pending.take() {
+            self.apply(transition, renderer, camera.clone());
+        }
+        self.render_scenes(renderer, camera, Some(alpha));
+    }
+
+    fn user_resize(&mut self, renderer: &mut Renderer, width: usize, height: usize) {
+        for scene in self.scenes.iter_mut() {
+            scene.resize(renderer, width, height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, time::Duration};
+
+    use crate::{
+        backend::headless::{EventScript, Headless, ScriptedEvent},
+        context::time::{FixedStep, TimeInfo},
+        engine::{camera_2d::Camera2dRef, two_d::Engine},
+        events::{input::Events, keyboard::K},
+        renderer::two_d::Renderer,
+        state::scene::{RootScene, Scene, SceneStack, Transition},
+    };
+
+    thread_local! {
+        static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn log(entry: &'static str) {
+        LOG.with(|l| l.borrow_mut().push(entry));
+    }
+
+    struct Game;
+    struct Pause;
+
+    fn game_input(events: &Events) -> Transition {
+        if events.key_pressed(K::P) {
+            return Transition::Push(Box::new(Pause));
+        }
+        if events.key_pressed(K::Escape) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+
+    fn pause_input(events: &Events) -> Transition {
+        if events.key_pressed(K::P) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+
+    impl Scene for Game {
+        fn init(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("game init");
+        }
+        fn update(
+            &mut self,
+            _renderer: &mut Renderer,
+            events: &Events,
+            _camera: Camera2dRef,
+            _time_info: &TimeInfo,
+        ) -> Transition {
+            game_input(events)
+        }
+        fn fixed_update(&mut self, events: &Events, _camera: Camera2dRef, _dt: f32) -> Transition {
+            game_input(events)
+        }
+        fn render(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("game render");
+        }
+        fn on_exit(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("game exit");
+        }
+    }
+
+    impl RootScene for Game {
+        fn create(_renderer: &mut Renderer, _camera: Camera2dRef) -> Self {
+            Game
+        }
+    }
+
+    impl Scene for Pause {
+        fn init(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("pause init");
+        }
+        fn update(
+            &mut self,
+            _renderer: &mut Renderer,
+            events: &Events,
+            _camera: Camera2dRef,
+            _time_info: &TimeInfo,
+        ) -> Transition {
+            pause_input(events)
+        }
+        fn fixed_update(&mut self, events: &Events, _camera: Camera2dRef, _dt: f32) -> Transition {
+            pause_input(events)
+        }
+        fn render(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("pause render");
+        }
+        fn is_overlay(&self) -> bool {
+            true
+        }
+        fn on_exit(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef) {
+            log("pause exit");
+        }
+    }
+
+    fn script() -> EventScript {
+        EventScript::new()
+            .at(1, ScriptedEvent::KeyDown(K::P))
+            .at(2, ScriptedEvent::KeyUp(K::P))
+            .at(3, ScriptedEvent::KeyDown(K::P))
+            .at(4, ScriptedEvent::KeyUp(K::P))
+            .at(4, ScriptedEvent::KeyDown(K::Escape))
+    }
+
+    // like in a window, a press shows up the frame after the key went down
+    const LOGGED: [&str; 11] = [
+        "game init",
+        "game render",
+        "game render",
+        "pause init",
+        "game render",
+        "pause render",
+        "game render",
+        "pause render",
+        "pause exit",
+        "game render",
+        "game exit",
+    ];
+
+    #[test]
+    fn push_overlay_pop() {
+        let backend = Headless::new(16, 16).with_frames(10).with_script(script());
+        let mut engine =
+            Engine::<SceneStack<Game>>::create_headless(backend, Default::default()).unwrap();
+        engine.run().unwrap();
+        assert!(engine.state().is_empty());
+        assert_eq!(LOG.with(|l| l.borrow().clone()), LOGGED);
+    }
+
+    #[test]
+    fn fixed_timestep() {
+        let backend = Headless::new(16, 16).with_script(script());
+        let mut engine =
+            Engine::<SceneStack<Game>>::create_headless(backend, Default::default()).unwrap();
+        engine.set_fixed_timestep(Some(FixedStep::new(10)));
+        // one tick a frame, the transitions land in the same frames as without ticks and
+        // the engine quits on the tick after the last pop
+        let frames = (0..10)
+            .position(|_| engine.frame(Duration::from_millis(100)).unwrap())
+            .unwrap();
+        assert_eq!(frames, 6);
+        assert!(engine.state().is_empty());
+        assert_eq!(LOG.with(|l| l.borrow().clone()), LOGGED);
+    }
+}
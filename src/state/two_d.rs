@@ -12,4 +12,18 @@ pub trait State {
         camera: Camera2dRef,
         time_info: &TimeInfo,
     ) -> bool;
+    /// Called after the window changed size. With [`ResizePolicy::Follow`] the
+    /// renderer buffer already has the new size.
+    ///
+    /// [`ResizePolicy::Follow`]: crate::renderer::two_d::ResizePolicy::Follow
+    fn user_resize(&mut self, _renderer: &mut Renderer, _width: usize, _height: usize) {}
+
+    /// Used instead of `user_update` once the engine runs with a fixed timestep.
+    /// Can be called zero or more times per frame, `dt` is always the tick length in seconds.
+    fn fixed_update(&mut self, _events: &Events, _camera: Camera2dRef, _dt: f32) -> bool {
+        false
+    }
+    /// Called once per frame after the ticks in fixed timestep mode. `alpha` is
+    /// the fraction of a tick that has passed since the last `fixed_update`.
+    fn render(&mut self, _renderer: &mut Renderer, _camera: Camera2dRef, _alpha: f32) {}
 }
@@ -0,0 +1,474 @@
+//! Frame by frame animation out of a sprite sheet, advanced with the frame time.
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{context::time::TimeInfo, linal::vertx2::VX2, objects::utils::Bounds};
+
+use super::blit::Blit;
+
+/// Shortest frame, so a clip of zero length frames can't spin forever.
+const MIN_FRAME_TIME: Duration = Duration::from_millis(1);
+/// Fastest playback, keeps the frame time times the speed a `Duration` and the number of
+/// frames stepped through in one update bounded.
+pub const MAX_SPEED: f32 = 1000.0;
+
+/// Rects of the frames in one texture, indexed in the order they were added.
+#[derive(Debug, Default, Clone)]
+pub struct SpriteSheet {
+    frames: Vec<Bounds>,
+}
+
+impl SpriteSheet {
+    /// Frames given one by one, in texels.
+    pub fn new(frames: Vec<Bounds>) -> Self {
+        Self { frames }
+    }
+
+    /// Equally sized frames, row by row from the top left of a `size` texture.
+    /// Frames that don't fit completely are left out.
+    pub fn grid(size: &VX2, frame_w: f32, frame_h: f32) -> Self {
+        Self::grid_spaced(size, frame_w, frame_h, 0.0, 0.0)
+    }
+
+    /// Like [`SpriteSheet::grid`], with `margin` around the whole sheet and `spacing`
+    /// between frames.
+    pub fn grid_spaced(size: &VX2, frame_w: f32, frame_h: f32, margin: f32, spacing: f32) -> Self {
+        let mut frames = Vec::new();
+        if frame_w <= 0.0 || frame_h <= 0.0 {
+            return Self { frames };
+        }
+        let mut y = margin;
+        while y + frame_h <= size.y - margin {
+            let mut x = margin;
+            while x + frame_w <= size.x - margin {
+                frames.push(Bounds::from_rect(x, y, frame_w, frame_h));
+                x += frame_w + spacing;
+            }
+            y += frame_h + spacing;
+        }
+        Self { frames }
+    }
+
+    pub fn push(&mut self, frame: Bounds) -> usize {
+        self.frames.push(frame);
+        self.frames.len() - 1
+    }
+    pub fn frame(&self, index: usize) -> Option<Bounds> {
+        self.frames.get(index).copied()
+    }
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// What a clip does after its last frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    /// stops on the last frame
+    Once,
+    /// plays backwards to the start again, then forwards
+    PingPong,
+}
+
+/// A named clip: sheet frames, how long each shows and events fired on entering one.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub name: String,
+    frames: Vec<usize>,
+    durations: Vec<Duration>,
+    pub mode: PlayMode,
+    /// position in the clip and the event name fired when it is entered
+    events: Vec<(usize, String)>,
+}
+
+impl Animation {
+    /// `frames` are sheet indices, each shown for `frame_time`.
+    pub fn new<I: IntoIterator<Item = usize>>(name: &str, frames: I, frame_time: Duration) -> Self {
+        let frames: Vec<usize> = frames.into_iter().collect();
+        Self {
+            name: name.to_string(),
+            durations: vec![frame_time.max(MIN_FRAME_TIME); frames.len()],
+            frames,
+            mode: PlayMode::Loop,
+            events: Vec::new(),
+        }
+    }
+    pub fn with_mode(mut self, mode: PlayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Per frame times, frames without one keep theirs.
+    pub fn with_durations(mut self, durations: &[Duration]) -> Self {
+        for (d, new) in self.durations.iter_mut().zip(durations) {
+            *d = (*new).max(MIN_FRAME_TIME);
+        }
+        self
+    }
+    /// Fires `event` every time the clip enters its `position`th frame.
+    pub fn with_event(mut self, position: usize, event: &str) -> Self {
+        self.events.push((position, event.to_string()));
+        self
+    }
+    pub fn frames(&self) -> &[usize] {
+        &self.frames
+    }
+    pub fn durations(&self) -> &[Duration] {
+        &self.durations
+    }
+    /// One pass, for ping-pong there and back.
+    pub fn total(&self) -> Duration {
+        let there: Duration = self.durations.iter().sum();
+        match self.mode {
+            PlayMode::PingPong if self.frames.len() > 2 => {
+                there + self.durations[1..self.durations.len() - 1].iter().sum()
+            }
+            _ => there,
+        }
+    }
+}
+
+/// Reported by [`AnimatedSprite::advance`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationEvent {
+    /// an event added with [`Animation::with_event`]
+    Frame { clip: String, event: String },
+    /// a looping or ping-pong clip started over
+    Looped { clip: String },
+    /// a one-shot clip reached its end
+    Finished { clip: String },
+    /// a queued clip took over from `from`
+    Switched { from: String, to: String },
+}
+
+/// Plays [`Animation`] clips over a [`SpriteSheet`]. The texture is kept by the caller,
+/// [`AnimatedSprite::blit`] gives the part of it to draw.
+#[derive(Debug, Clone)]
+pub struct AnimatedSprite {
+    sheet: SpriteSheet,
+    clips: Vec<Animation>,
+    current: usize,
+    position: usize,
+    backwards: bool,
+    elapsed: Duration,
+    finished: bool,
+    /// events of the current frame still have to be fired
+    entered: bool,
+    queue: VecDeque<usize>,
+    events: Vec<AnimationEvent>,
+    /// `events` were handed out by `advance` already and go before new ones come in
+    reported: bool,
+    /// clamped to 0.0..=[`MAX_SPEED`]
+    pub speed: f32,
+}
+
+impl AnimatedSprite {
+    pub fn new(sheet: SpriteSheet) -> Self {
+        Self {
+            sheet,
+            clips: Vec::new(),
+            current: 0,
+            position: 0,
+            backwards: false,
+            elapsed: Duration::ZERO,
+            finished: false,
+            entered: true,
+            queue: VecDeque::new(),
+            events: Vec::new(),
+            reported: false,
+            speed: 1.0,
+        }
+    }
+    /// Adds a clip, the first one added plays right away.
+    pub fn with_clip(mut self, clip: Animation) -> Self {
+        self.add_clip(clip);
+        self
+    }
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+    /// Replaces a clip of the same name.
+    pub fn add_clip(&mut self, clip: Animation) {
+        match self.clips.iter().position(|c| c.name == clip.name) {
+            Some(i) => self.clips[i] = clip,
+            None => self.clips.push(clip),
+        }
+    }
+    pub fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+    pub fn clip(&self) -> Option<&Animation> {
+        self.clips.get(self.current)
+    }
+
+    /// Switches to `name` right away and drops the queue. Does nothing if it is already
+    /// playing, false if there is no such clip.
+    pub fn play(&mut self, name: &str) -> bool {
+        let Some(index) = self.find(name) else {
+            return false;
+        };
+        self.queue.clear();
+        if index != self.current || self.finished {
+            self.start(index);
+        }
+        true
+    }
+    /// Plays `name` once the current clip ends: a one-shot at its last frame, a looping one
+    /// at the end of its current pass. False if there is no such clip.
+    pub fn queue(&mut self, name: &str) -> bool {
+        let Some(index) = self.find(name) else {
+            return false;
+        };
+        self.queue.push_back(index);
+        if self.finished {
+            self.next_queued();
+        }
+        true
+    }
+    /// Current clip from its first frame.
+    pub fn restart(&mut self) {
+        self.start(self.current);
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Sheet index of the frame showing.
+    pub fn frame(&self) -> Option<usize> {
+        self.clip()
+            .and_then(|c| c.frames.get(self.position).copied())
+    }
+    /// Texels of the frame showing.
+    pub fn src(&self) -> Option<Bounds> {
+        self.frame().and_then(|f| self.sheet.frame(f))
+    }
+    /// [`Blit`] of the frame showing at `pos`, whole texture if there is none.
+    pub fn blit(&self, pos: VX2) -> Blit {
+        let blit = Blit::new(pos);
+        match self.src() {
+            Some(src) => blit.with_src(src),
+            None => blit,
+        }
+    }
+
+    /// Advances by the last frame time.
+    pub fn update(&mut self, time: &TimeInfo) -> &[AnimationEvent] {
+        self.advance(time.delta())
+    }
+
+    /// Moves on by `delta` times the speed, returns what happened on the way, including a
+    /// switch `queue` did since the last call.
+    pub fn advance(&mut self, delta: Duration) -> &[AnimationEvent] {
+        self.drop_reported();
+        if self.clip().is_none_or(|c| c.frames.is_empty()) {
+            self.reported = true;
+            return &self.events;
+        }
+        if self.entered {
+            self.enter();
+        }
+        let speed = match self.speed.is_nan() {
+            true => 0.0,
+            false => self.speed.clamp(0.0, MAX_SPEED),
+        };
+        // only fails for deltas no frame ever takes
+        let scaled = Duration::try_from_secs_f64(delta.as_secs_f64() * speed as f64);
+        let mut left = scaled.unwrap_or(Duration::ZERO);
+        while !self.finished {
+            let shown = self.clips[self.current].durations[self.position];
+            if self.elapsed + left < shown {
+                self.elapsed += left;
+                break;
+            }
+            left -= shown - self.elapsed;
+            self.elapsed = Duration::ZERO;
+            self.step();
+        }
+        self.reported = true;
+        &self.events
+    }
+
+    fn drop_reported(&mut self) {
+        if std::mem::take(&mut self.reported) {
+            self.events.clear();
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.clips.iter().position(|c| c.name == name)
+    }
+
+    fn start(&mut self, index: usize) {
+        self.current = index;
+        self.position = 0;
+        self.backwards = false;
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.entered = true;
+    }
+
+    /// Fires the events of the frame we are on.
+    fn enter(&mut self) {
+        self.entered = false;
+        let clip = &self.clips[self.current];
+        for (_, event) in clip.events.iter().filter(|(p, _)| *p == self.position) {
+            self.events.push(AnimationEvent::Frame {
+                clip: clip.name.clone(),
+                event: event.clone(),
+            });
+        }
+    }
+
+    /// Starts the next queued clip, false if there is none.
+    fn next_queued(&mut self) -> bool {
+        let Some(next) = self.queue.pop_front() else {
+            return false;
+        };
+        let from = self.clips[self.current].name.clone();
+        self.start(next);
+        let to = self.clips[next].name.clone();
+        // from `queue` this is outside of `advance`
+        self.drop_reported();
+        self.events.push(AnimationEvent::Switched { from, to });
+        true
+    }
+
+    /// Goes to the next frame at the end of the current one.
+    fn step(&mut self) {
+        let clip = &self.clips[self.current];
+        let (len, mode) = (clip.frames.len(), clip.mode);
+        let name = clip.name.clone();
+        let last = len.saturating_sub(1);
+        let pass_done = match mode {
+            // two frames back and forth is the same as looping them
+            PlayMode::PingPong if last > 1 => self.backwards && self.position == 1,
+            _ => self.position >= last,
+        };
+        if pass_done {
+            if self.next_queued() {
+                self.enter();
+                return;
+            }
+            if mode == PlayMode::Once {
+                self.finished = true;
+                self.events.push(AnimationEvent::Finished { clip: name });
+                return;
+            }
+            self.events.push(AnimationEvent::Looped { clip: name });
+        }
+        self.position = match mode {
+            _ if pass_done => {
+                self.backwards = false;
+                0
+            }
+            PlayMode::PingPong if self.position == last => {
+                self.backwards = true;
+                last - 1
+            }
+            PlayMode::PingPong if self.backwards => self.position - 1,
+            _ => self.position + 1,
+        };
+        self.enter();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        objects::utils::Bounds,
+        textures::animation::{AnimatedSprite, Animation, AnimationEvent, PlayMode, SpriteSheet},
+        vx2,
+    };
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn modes_events_and_queue() {
+        let sheet = SpriteSheet::grid_spaced(&vx2!(34.0, 18.0), 8.0, 8.0, 1.0, 0.0);
+        assert_eq!(sheet.len(), 8);
+        assert_eq!(sheet.frame(5), Some(Bounds::from_rect(9.0, 9.0, 8.0, 8.0)));
+
+        let mut sprite = AnimatedSprite::new(sheet)
+            .with_clip(
+                Animation::new("idle", 0..3, ms(100))
+                    .with_mode(PlayMode::PingPong)
+                    .with_event(2, "blink"),
+            )
+            .with_clip(
+                Animation::new("attack", [4, 5], ms(50))
+                    .with_mode(PlayMode::Once)
+                    .with_durations(&[ms(50), ms(200)])
+                    .with_event(1, "hit"),
+            );
+
+        // ping-pong: 0 1 2 1 | 0 1 ...
+        let mut shown = Vec::new();
+        for _ in 0..5 {
+            shown.push(sprite.frame().unwrap());
+            sprite.advance(ms(100));
+        }
+        shown.push(sprite.frame().unwrap());
+        assert_eq!(shown, vec![0, 1, 2, 1, 0, 1]);
+        assert_eq!(
+            sprite.advance(ms(350)),
+            &[
+                AnimationEvent::Frame {
+                    clip: "idle".to_string(),
+                    event: "blink".to_string()
+                },
+                AnimationEvent::Looped {
+                    clip: "idle".to_string()
+                }
+            ]
+        );
+
+        // the queued one-shot waits for the end of the pass, twice as fast
+        sprite.speed = 2.0;
+        assert!(sprite.queue("attack"));
+        assert!(!sprite.queue("jump"));
+        assert!(sprite.advance(ms(25)).is_empty());
+        assert_eq!(sprite.frame(), Some(1));
+        let events = sprite.advance(ms(150)).to_vec();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            AnimationEvent::Switched {
+                from: "idle".to_string(),
+                to: "attack".to_string()
+            }
+        );
+        assert_eq!(sprite.frame(), Some(4));
+        assert_eq!(sprite.advance(ms(25)).len(), 1);
+        assert_eq!(sprite.src(), Some(Bounds::from_rect(9.0, 9.0, 8.0, 8.0)));
+        let events = sprite.advance(ms(500));
+        assert!(matches!(events, [AnimationEvent::Finished { .. }]));
+        assert!(sprite.is_finished());
+        assert_eq!(sprite.frame(), Some(5));
+
+        sprite.queue("idle");
+        assert!(!sprite.is_finished());
+        assert_eq!(sprite.frame(), Some(0));
+        // the switch happened in `queue`, the next update still reports it
+        assert_eq!(
+            sprite.advance(ms(10)),
+            &[AnimationEvent::Switched {
+                from: "attack".to_string(),
+                to: "idle".to_string()
+            }]
+        );
+
+        for speed in [f32::INFINITY, f32::NAN, 1e30] {
+            sprite.speed = speed;
+            sprite.advance(ms(16));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Sprites made with Aseprite: frames, layers, tags and slices as textures and rects.
+
+use std::path::Path;
+
+use asefile::{AnimationDirection, AsepriteFile, LayerType};
+
+use crate::{
+    error::{GrapesError, ResultG},
+    linal::vertx2::VX2,
+    objects::utils::Bounds,
+    vx2,
+};
+
+use super::Texture;
+
+/// Order the frames of a tag are played in.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TagDirection {
+    #[default]
+    Forward,
+    Reverse,
+    /// forward, then back again without repeating the last and first frame
+    PingPong,
+}
+
+/// One frame with all visible layers flattened.
+#[derive(Clone)]
+pub struct AseFrame {
+    pub texture: Texture,
+    pub duration_ms: u32,
+}
+
+/// A single image layer, one sprite sized texture per frame. Group layers are left out.
+#[derive(Clone)]
+pub struct AseLayer {
+    pub name: String,
+    pub visible: bool,
+    pub opacity: u8,
+    pub frames: Vec<Texture>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AseTag {
+    pub name: String,
+    /// first frame, inclusive
+    pub from: usize,
+    /// last frame, inclusive
+    pub to: usize,
+    pub direction: TagDirection,
+    /// times to play the tag, None for forever
+    pub repeat: Option<u32>,
+}
+
+impl AseTag {
+    /// Frame indices of one pass through the tag, in playing order.
+    pub fn frames(&self) -> Vec<usize> {
+        let forward = self.from..=self.to;
+        match self.direction {
+            TagDirection::Forward => forward.collect(),
+            TagDirection::Reverse => forward.rev().collect(),
+            TagDirection::PingPong => {
+                let back = (self.from + 1..self.to).rev();
+                forward.chain(back).collect()
+            }
+        }
+    }
+}
+
+/// Where a slice is from `from_frame` on, in sprite pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct AseSliceKey {
+    pub from_frame: usize,
+    pub bounds: Bounds,
+    /// nine-slice centre, inside `bounds`
+    pub center: Option<Bounds>,
+    /// relative to the top left of `bounds`
+    pub pivot: Option<VX2>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AseSlice {
+    pub name: String,
+    pub keys: Vec<AseSliceKey>,
+}
+
+impl AseSlice {
+    /// The key in effect at `frame`.
+    pub fn key(&self, frame: usize) -> Option<&AseSliceKey> {
+        self.keys.iter().rev().find(|k| k.from_frame <= frame)
+    }
+}
+
+/// Everything in an `.aseprite` or `.ase` file, decoded up front.
+#[derive(Clone)]
+pub struct Aseprite {
+    pub width: usize,
+    pub height: usize,
+    pub frames: Vec<AseFrame>,
+    pub layers: Vec<AseLayer>,
+    pub tags: Vec<AseTag>,
+    pub slices: Vec<AseSlice>,
+}
+
+impl Aseprite {
+    pub fn load<P: AsRef<Path>>(path: P) -> ResultG<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| GrapesError::LoadingResource(format!("{}: {e}", path.display())))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> ResultG<Self> {
+        let file = AsepriteFile::read(bytes)
+            .map_err(|e| GrapesError::ParsingResource(format!("aseprite: {e}")))?;
+        let (width, height) = file.size();
+        let texture = |rgba: &[u8]| {
+            let pixels: Vec<u32> = rgba
+                .chunks_exact(4)
+                .map(|p| u32::from_be_bytes([p[3], p[0], p[1], p[2]]))
+                .collect();
+            Texture::from_pixels(width, height, &pixels)
+                .ok_or_else(|| GrapesError::ParsingResource("aseprite: empty sprite".to_string()))
+        };
+
+        let mut frames = Vec::new();
+        for f in 0..file.num_frames() {
+            let frame = file.frame(f);
+            frames.push(AseFrame {
+                texture: texture(frame.image().as_raw())?,
+                duration_ms: frame.duration(),
+            });
+        }
+
+        let mut layers = Vec::new();
+        for layer in file.layers() {
+            if layer.layer_type() == LayerType::Group {
+                continue;
+            }
+            let mut textures = Vec::new();
+            for f in 0..file.num_frames() {
+                textures.push(texture(layer.frame(f).image().as_raw())?);
+            }
+            layers.push(AseLayer {
+                name: layer.name().to_string(),
+                visible: layer.is_visible(),
+                opacity: layer.opacity(),
+                frames: textures,
+            });
+        }
+
+        let tags = (0..file.num_tags())
+            .map(|id| {
+                let tag = file.tag(id);
+                AseTag {
+                    name: tag.name().to_string(),
+                    from: tag.from_frame() as usize,
+                    to: tag.to_frame() as usize,
+                    direction: match tag.animation_direction() {
+                        AnimationDirection::Forward => TagDirection::Forward,
+                        AnimationDirection::Reverse => TagDirection::Reverse,
+                        AnimationDirection::PingPong => TagDirection::PingPong,
+                    },
+                    repeat: tag.repeat().map(|n| n.get()),
+                }
+            })
+            .collect();
+
+        let slices = file
+            .slices()
+            .iter()
+            .map(|slice| AseSlice {
+                name: slice.name.clone(),
+                keys: slice
+                    .keys
+                    .iter()
+                    .map(|key| {
+                        let (x, y) = (key.origin.0 as f32, key.origin.1 as f32);
+                        AseSliceKey {
+                            from_frame: key.from_frame as usize,
+                            bounds: Bounds::from_rect(x, y, key.size.0 as f32, key.size.1 as f32),
+                            center: key.slice9.as_ref().map(|c| {
+                                Bounds::from_rect(
+                                    x + c.center_x as f32,
+                                    y + c.center_y as f32,
+                                    c.center_width as f32,
+                                    c.center_height as f32,
+                                )
+                            }),
+                            pivot: key.pivot.map(|(px, py)| vx2!(px as f32, py as f32)),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Self {
+            width,
+            height,
+            frames,
+            layers,
+            tags,
+            slices,
+        })
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&AseTag> {
+        self.tags.iter().find(|t| t.name == name)
+    }
+    pub fn layer(&self, name: &str) -> Option<&AseLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+    pub fn slice(&self, name: &str) -> Option<&AseSlice> {
+        self.slices.iter().find(|s| s.name == name)
+    }
+
+    /// Frame durations of one pass through `tag`, in playing order.
+    pub fn durations(&self, tag: &AseTag) -> Vec<u32> {
+        tag.frames()
+            .into_iter()
+            .filter_map(|f| self.frames.get(f).map(|f| f.duration_ms))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        objects::utils::Bounds,
+        textures::{
+            aseprite::{Aseprite, TagDirection},
+            decode::argb,
+        },
+    };
+
+    fn chunk(out: &mut Vec<u8>, kind: u16, data: &[u8]) {
+        out.extend((data.len() as u32 + 6).to_le_bytes());
+        out.extend(kind.to_le_bytes());
+        out.extend(data);
+    }
+
+    fn string(out: &mut Vec<u8>, s: &str) {
+        out.extend((s.len() as u16).to_le_bytes());
+        out.extend(s.as_bytes());
+    }
+
+    /// 2x1 rgba sprite, layers "back" and "top", three frames.
+    fn sprite() -> Vec<u8> {
+        let mut frames = Vec::new();
+        for f in 0..3u8 {
+            let mut chunks = Vec::new();
+            let mut count = 0u16;
+            if f == 0 {
+                for name in ["back", "top"] {
+                    let mut layer = vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 0, 0, 0];
+                    string(&mut layer, name);
+                    chunk(&mut chunks, 0x2004, &layer);
+                }
+                let mut tags = 1u16.to_le_bytes().to_vec();
+                tags.extend([0; 8]);
+                tags.extend([0, 0, 2, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+                string(&mut tags, "walk");
+                chunk(&mut chunks, 0x2018, &tags);
+                let mut slice = vec![1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0];
+                string(&mut slice, "panel");
+                for v in [0u32, 0, 0, 2, 1, 1, 0, 1, 1] {
+                    slice.extend(v.to_le_bytes());
+                }
+                chunk(&mut chunks, 0x2022, &slice);
+                count += 4;
+            }
+            // raw cels: "back" opaque red on the left, "top" in the right pixel, blue at frame f
+            let mut back = vec![0, 0, 0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+            back.extend([1, 0, 1, 0, 255, 0, 0, 255]);
+            chunk(&mut chunks, 0x2005, &back);
+            let mut top = vec![1, 0, 1, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+            top.extend([1, 0, 1, 0, 0, 0, 50 * f, 255]);
+            chunk(&mut chunks, 0x2005, &top);
+            count += 2;
+
+            frames.extend((chunks.len() as u32 + 16).to_le_bytes());
+            frames.extend(0xF1FAu16.to_le_bytes());
+            frames.extend(count.to_le_bytes());
+            frames.extend((100 * (f as u16 + 1)).to_le_bytes());
+            frames.extend([0; 6]);
+            frames.extend(chunks);
+        }
+        let mut out = ((128 + frames.len()) as u32).to_le_bytes().to_vec();
+        out.extend(0xA5E0u16.to_le_bytes());
+        for v in [3u16, 2, 1, 32] {
+            out.extend(v.to_le_bytes());
+        }
+        out.resize(128, 0);
+        out.extend(frames);
+        out
+    }
+
+    #[test]
+    fn frames_layers_tags_and_slices() {
+        let ase = Aseprite::from_bytes(&sprite()).unwrap();
+        assert_eq!((ase.width, ase.height, ase.frames.len()), (2, 1, 3));
+        let px = |f: usize| ase.frames[f].texture.get_buffer().as_slice().to_vec();
+        assert_eq!(px(2), vec![argb(255, 0, 0, 255), argb(0, 0, 100, 255)]);
+
+        assert_eq!(ase.layers.len(), 2);
+        let top = ase.layer("top").unwrap();
+        assert_eq!(top.frames[1].get_buffer().as_slice()[0], 0);
+
+        let walk = ase.tag("walk").unwrap();
+        assert_eq!(walk.direction, TagDirection::PingPong);
+        assert_eq!(walk.frames(), vec![0, 1, 2, 1]);
+        assert_eq!(ase.durations(walk), vec![100, 200, 300, 200]);
+
+        let key = ase.slice("panel").unwrap().key(2).unwrap();
+        assert_eq!(key.bounds, Bounds::from_rect(0.0, 0.0, 2.0, 1.0));
+        assert_eq!(key.center, Some(Bounds::from_rect(1.0, 0.0, 1.0, 1.0)));
+    }
+}
@@ -0,0 +1,520 @@
+//! Packs many small images into one texture, each addressed by the rect it landed in.
+
+use std::{fmt::Write as _, path::Path};
+
+use crate::{
+    error::{GrapesError, ResultG},
+    linal::vertx2::VX2,
+    objects::utils::Bounds,
+    vx2,
+};
+
+use super::{Texture, blit::Blit};
+
+/// How free space gets handed out.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Packer {
+    /// fast, good for many similar sized items added one at a time, like glyphs
+    #[default]
+    Skyline,
+    /// slower, packs mixed sizes tighter
+    MaxRects,
+}
+
+/// Refers to one image in an [`Atlas`], until the atlas gets cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AtlasId {
+    index: usize,
+    /// of the atlas when the id was handed out
+    generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl Rect {
+    fn right(&self) -> u32 {
+        self.x + self.w
+    }
+    fn bottom(&self) -> u32 {
+        self.y + self.h
+    }
+    fn overlaps(&self, o: &Rect) -> bool {
+        self.x < o.right() && o.x < self.right() && self.y < o.bottom() && o.y < self.bottom()
+    }
+    fn contains(&self, o: &Rect) -> bool {
+        o.x >= self.x && o.y >= self.y && o.right() <= self.right() && o.bottom() <= self.bottom()
+    }
+}
+
+/// A run of the top edge of what's been packed so far.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    w: u32,
+}
+
+#[derive(Debug, Clone)]
+enum Space {
+    Skyline(Vec<Segment>),
+    MaxRects(Vec<Rect>),
+}
+
+impl Space {
+    fn new(packer: Packer, w: u32, h: u32) -> Self {
+        match packer {
+            Packer::Skyline => Space::Skyline(vec![Segment { x: 0, y: 0, w }]),
+            Packer::MaxRects => Space::MaxRects(vec![Rect { x: 0, y: 0, w, h }]),
+        }
+    }
+
+    /// Reserves a `w` by `h` spot inside `width` by `height`.
+    fn place(&mut self, w: u32, h: u32, width: u32, height: u32) -> Option<Rect> {
+        match self {
+            Space::Skyline(segments) => skyline_place(segments, w, h, width, height),
+            Space::MaxRects(free) => max_rects_place(free, w, h),
+        }
+    }
+}
+
+/// Bottom left: the spot that keeps the top edge lowest.
+fn skyline_place(
+    segments: &mut Vec<Segment>,
+    w: u32,
+    h: u32,
+    width: u32,
+    height: u32,
+) -> Option<Rect> {
+    // y the item would sit at when its left edge is on segment `i`
+    let fit = |i: usize| -> Option<u32> {
+        let x = segments[i].x;
+        if x + w > width {
+            return None;
+        }
+        let (mut y, mut covered) = (0, 0);
+        for s in &segments[i..] {
+            if covered >= w {
+                break;
+            }
+            y = y.max(s.y);
+            covered += s.w;
+        }
+        (covered >= w && y + h <= height).then_some(y)
+    };
+    let (i, y) = (0..segments.len())
+        .filter_map(|i| fit(i).map(|y| (i, y)))
+        .min_by_key(|&(i, y)| (y + h, segments[i].w))?;
+    let rect = Rect {
+        x: segments[i].x,
+        y,
+        w,
+        h,
+    };
+
+    segments.insert(
+        i,
+        Segment {
+            x: rect.x,
+            y: rect.bottom(),
+            w,
+        },
+    );
+    // cut away what the new segment now covers
+    let end = rect.right();
+    while let Some(next) = segments.get_mut(i + 1) {
+        if next.x >= end {
+            break;
+        }
+        let cut = end - next.x;
+        if next.w <= cut {
+            segments.remove(i + 1);
+        } else {
+            next.x += cut;
+            next.w -= cut;
+            break;
+        }
+    }
+    // join neighbours at the same height
+    let mut j = 0;
+    while j + 1 < segments.len() {
+        if segments[j].y == segments[j + 1].y {
+            segments[j].w += segments[j + 1].w;
+            segments.remove(j + 1);
+        } else {
+            j += 1;
+        }
+    }
+    Some(rect)
+}
+
+/// Best short side fit, then splits every free rect the new one overlaps.
+fn max_rects_place(free: &mut Vec<Rect>, w: u32, h: u32) -> Option<Rect> {
+    let best = free
+        .iter()
+        .filter(|f| f.w >= w && f.h >= h)
+        .min_by_key(|f| {
+            let (dw, dh) = (f.w - w, f.h - h);
+            (dw.min(dh), dw.max(dh))
+        })?;
+    let rect = Rect {
+        x: best.x,
+        y: best.y,
+        w,
+        h,
+    };
+
+    let mut split = Vec::new();
+    free.retain(|f| {
+        if !f.overlaps(&rect) {
+            return true;
+        }
+        if rect.x > f.x {
+            split.push(Rect {
+                w: rect.x - f.x,
+                ..*f
+            });
+        }
+        if rect.right() < f.right() {
+            split.push(Rect {
+                x: rect.right(),
+                w: f.right() - rect.right(),
+                ..*f
+            });
+        }
+        if rect.y > f.y {
+            split.push(Rect {
+                h: rect.y - f.y,
+                ..*f
+            });
+        }
+        if rect.bottom() < f.bottom() {
+            split.push(Rect {
+                y: rect.bottom(),
+                h: f.bottom() - rect.bottom(),
+                ..*f
+            });
+        }
+        false
+    });
+    free.extend(split);
+    // drop free rects that lie inside another one
+    let mut i = 0;
+    while i < free.len() {
+        let inside = (0..free.len())
+            .any(|j| j != i && free[j].contains(&free[i]) && (free[j] != free[i] || j < i));
+        if inside {
+            free.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    Some(rect)
+}
+
+/// One big texture that images get packed into. Items can be added at any time, until
+/// there is no room left.
+#[derive(Clone)]
+pub struct Atlas {
+    texture: Texture,
+    packer: Packer,
+    space: Space,
+    /// empty texels between neighbours
+    padding: u32,
+    /// how far the edge texels of every item get repeated outwards, keeps bilinear
+    /// sampling from bleeding in the neighbours
+    extrude: u32,
+    regions: Vec<Bounds>,
+    names: Vec<(String, AtlasId)>,
+    /// bumped by `clear`, ids from before don't refer to anything anymore
+    generation: u32,
+}
+
+impl Atlas {
+    /// None if the texture would be too big, its texel count has to fit an i32.
+    pub fn new(width: u32, height: u32, packer: Packer) -> Option<Self> {
+        let (width, height) = (width.max(1), height.max(1));
+        i32::try_from(width)
+            .ok()?
+            .checked_mul(i32::try_from(height).ok()?)?;
+        Some(Self {
+            texture: Texture::init(vx2!(width as f32, height as f32)),
+            packer,
+            space: Space::new(packer, width, height),
+            padding: 0,
+            extrude: 0,
+            regions: Vec::new(),
+            names: Vec::new(),
+            generation: 0,
+        })
+    }
+    /// Set it before adding anything, the free space starts over.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        // padding only needs to be on one side, the atlas edge is as good as a gap
+        let (w, h) = (self.width() + padding, self.height() + padding);
+        self.space = Space::new(self.packer, w, h);
+        self
+    }
+    /// Only applies to items added after.
+    pub fn with_extrude(mut self, extrude: u32) -> Self {
+        self.extrude = extrude;
+        self
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Drops every item and clears the texture, the space is free again. Ids handed out
+    /// before don't find anything anymore.
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        let (w, h) = (self.width() + self.padding, self.height() + self.padding);
+        self.space = Space::new(self.packer, w, h);
+        self.regions.clear();
+        self.names.clear();
+        self.texture.get_buffer_mut().fill(0);
+    }
+
+    /// Copies `texture` in, None if it doesn't fit anymore.
+    pub fn insert(&mut self, texture: &Texture) -> Option<AtlasId> {
+        let buffer = texture.get_buffer();
+        let (w, h) = (buffer.width() as u32, buffer.height() as u32);
+        self.insert_pixels(w, h, buffer.as_slice())
+    }
+
+    /// Like [`Atlas::insert`], findable by `name` afterwards.
+    pub fn insert_named(&mut self, name: &str, texture: &Texture) -> Option<AtlasId> {
+        let id = self.insert(texture)?;
+        self.names.push((name.to_string(), id));
+        Some(id)
+    }
+
+    /// Adds x11 ARGB pixels, row by row. None if they don't fit or don't match the size.
+    pub fn insert_pixels(&mut self, width: u32, height: u32, pixels: &[u32]) -> Option<AtlasId> {
+        let len = (width as usize).checked_mul(height as usize);
+        if width == 0 || height == 0 || len != Some(pixels.len()) {
+            return None;
+        }
+        let (aw, ah) = (self.width(), self.height());
+        let border = 2 * self.extrude + self.padding;
+        let spot = self.space.place(
+            width + border,
+            height + border,
+            aw + self.padding,
+            ah + self.padding,
+        )?;
+        let (x0, y0) = (spot.x + self.extrude, spot.y + self.extrude);
+
+        let e = self.extrude as i64;
+        let target = self.texture.get_buffer_mut().as_mut_slice();
+        for y in -e..height as i64 + e {
+            let sy = y.clamp(0, height as i64 - 1) as usize;
+            for x in -e..width as i64 + e {
+                let sx = x.clamp(0, width as i64 - 1) as usize;
+                let (tx, ty) = (x0 as i64 + x, y0 as i64 + y);
+                if tx < aw as i64 && ty < ah as i64 {
+                    target[ty as usize * aw as usize + tx as usize] =
+                        pixels[sy * width as usize + sx];
+                }
+            }
+        }
+
+        let region = Bounds::from_rect(x0 as f32, y0 as f32, width as f32, height as f32);
+        self.regions.push(region);
+        Some(AtlasId {
+            index: self.regions.len() - 1,
+            generation: self.generation,
+        })
+    }
+
+    /// Packs all of them, biggest first, which fills the space a lot better than adding
+    /// them in any order. Ids come back in the order given, None if they didn't all fit,
+    /// the atlas is left as it was then.
+    pub fn insert_all(&mut self, items: &[(&str, &Texture)]) -> Option<Vec<AtlasId>> {
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&i| {
+            let size = items[i].1.size();
+            std::cmp::Reverse((size.y as u32, size.x as u32))
+        });
+        let (space, count, named) = (self.space.clone(), self.regions.len(), self.names.len());
+        let mut ids = vec![None; items.len()];
+        for i in order {
+            let (name, texture) = items[i];
+            let Some(id) = self.insert_named(name, texture) else {
+                self.roll_back(space, count, named);
+                return None;
+            };
+            ids[i] = Some(id);
+        }
+        ids.into_iter().collect()
+    }
+
+    /// Takes out the items from `count` on, the texels they went to were free before.
+    fn roll_back(&mut self, space: Space, count: usize, named: usize) {
+        let (aw, ah) = (self.width() as usize, self.height() as usize);
+        let e = self.extrude as f32;
+        let target = self.texture.get_buffer_mut().as_mut_slice();
+        for r in self.regions.drain(count..) {
+            let (x0, x1) = ((r.min_x - e) as usize, ((r.max_x + e) as usize).min(aw));
+            for y in (r.min_y - e) as usize..((r.max_y + e) as usize).min(ah) {
+                target[y * aw + x0..y * aw + x1].fill(0);
+            }
+        }
+        self.names.truncate(named);
+        self.space = space;
+    }
+
+    pub fn get(&self, name: &str) -> Option<AtlasId> {
+        self.names
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, id)| *id)
+    }
+
+    /// Texels of the item, the `src` of a [`Blit`]. None for ids from before a `clear`.
+    pub fn region(&self, id: AtlasId) -> Option<Bounds> {
+        if id.generation != self.generation {
+            return None;
+        }
+        self.regions.get(id.index).copied()
+    }
+
+    /// [`Blit`] of the item at `pos`, draw it with [`Atlas::texture`].
+    pub fn blit(&self, id: AtlasId, pos: VX2) -> Option<Blit> {
+        Some(Blit::new(pos).with_src(self.region(id)?))
+    }
+
+    /// One line per item, `x y width height name`, after an `atlas width height` line.
+    /// Items without a name get their index.
+    pub fn layout(&self) -> String {
+        let mut out = format!("atlas {} {}\n", self.width(), self.height());
+        for (i, r) in self.regions.iter().enumerate() {
+            let name = self
+                .names
+                .iter()
+                .find(|(_, id)| id.index == i)
+                .map_or_else(|| i.to_string(), |(n, _)| n.clone());
+            let _ = writeln!(
+                out,
+                "{} {} {} {} {name}",
+                r.min_x,
+                r.min_y,
+                r.width(),
+                r.height()
+            );
+        }
+        out
+    }
+
+    pub fn save_layout<P: AsRef<Path>>(&self, path: P) -> ResultG<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.layout())
+            .map_err(|e| GrapesError::SavingResource(format!("{}: {e}", path.display())))
+    }
+
+    fn width(&self) -> u32 {
+        self.texture.get_buffer().width() as u32
+    }
+    fn height(&self) -> u32 {
+        self.texture.get_buffer().height() as u32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::GrapesError,
+        textures::{
+            Texture,
+            atlas::{Atlas, Packer},
+        },
+        vx2,
+    };
+
+    #[test]
+    fn packs_without_overlap() {
+        for packer in [Packer::Skyline, Packer::MaxRects] {
+            let mut atlas = Atlas::new(64, 64, packer).unwrap().with_padding(1);
+            let mut ids = Vec::new();
+            for i in 0..40u32 {
+                let (w, h) = (3 + i % 7, 2 + i % 5);
+                match atlas.insert_pixels(w, h, &vec![i; (w * h) as usize]) {
+                    Some(id) => ids.push(id),
+                    None => break,
+                }
+            }
+            assert!(ids.len() > 30, "{packer:?} packed {}", ids.len());
+            for (i, a) in ids.iter().enumerate() {
+                let a = atlas.region(*a).unwrap();
+                assert!(a.max_x <= 64.0 && a.max_y <= 64.0);
+                for b in &ids[i + 1..] {
+                    let gap = a.intersect(&atlas.region(*b).unwrap());
+                    assert!(gap.is_empty(), "{packer:?} overlap {a:?} {gap:?}");
+                }
+                let px = atlas.texture().get_buffer().as_slice();
+                assert_eq!(px[a.min_y as usize * 64 + a.min_x as usize], i as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn extrude_names_and_layout() {
+        let mut atlas = Atlas::new(10, 8, Packer::MaxRects).unwrap().with_extrude(1);
+        let mut dot = Texture::init(vx2!(2.0, 1.0));
+        dot.get_buffer_mut().set(0, 7);
+        dot.get_buffer_mut().set(1, 9);
+        let big = Texture::init(vx2!(4.0, 4.0));
+        let ids = atlas.insert_all(&[("dot", &dot), ("big", &big)]).unwrap();
+        assert_eq!(atlas.get("dot"), Some(ids[0]));
+
+        let r = atlas.region(ids[0]).unwrap();
+        let px = atlas.texture().get_buffer().as_slice();
+        let at = |x: f32, y: f32| px[y as usize * 10 + x as usize];
+        assert_eq!(at(r.min_x - 1.0, r.min_y - 1.0), 7);
+        assert_eq!(at(r.max_x, r.min_y + 1.0), 9);
+        assert!(atlas.insert(&big).is_none());
+        assert_eq!(
+            atlas.layout(),
+            format!("atlas 10 8\n1 1 4 4 big\n{} {} 2 1 dot\n", r.min_x, r.min_y)
+        );
+
+        // ids from before a clear don't find the items added after it
+        atlas.clear();
+        let again = atlas.insert(&dot).unwrap();
+        assert!(atlas.region(ids[0]).is_none() && atlas.blit(ids[1], vx2!(0.0, 0.0)).is_none());
+        assert!(atlas.region(again).is_some());
+        assert!(atlas.insert_pixels(u32::MAX, u32::MAX, &[0]).is_none());
+        assert!(Atlas::new(65536, 65536, Packer::Skyline).is_none());
+
+        // the big one fits, the wide one doesn't and takes the big one out with it
+        atlas.clear();
+        let wide = Texture::init(vx2!(9.0, 1.0));
+        let mut big = big;
+        big.get_buffer_mut().fill(5);
+        assert!(
+            atlas
+                .insert_all(&[("wide", &wide), ("big", &big)])
+                .is_none()
+        );
+        assert!(atlas.is_empty() && atlas.get("big").is_none());
+        let px = atlas.texture().get_buffer().as_slice();
+        assert!(px.iter().all(|p| *p == 0));
+        assert!(atlas.insert(&Texture::init(vx2!(8.0, 6.0))).is_some());
+        let saved = atlas.save_layout("does/not/exist/atlas.txt");
+        assert!(matches!(saved, Err(GrapesError::SavingResource(_))));
+        assert!(Atlas::new(u32::MAX, 1, Packer::Skyline).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{buffer::Buffer, linal::vertx2::VX2, objects::utils::Bounds, vx2};
+
+/// How texels get picked when a texture is drawn scaled or rotated.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    #[default]
+    Nearest,
+    /// blends the four closest texels, never reads outside the source rect
+    Bilinear,
+}
+
+/// Describes one textured quad for [`crate::renderer::two_d::Renderer::blit`].
+/// Starts out as the whole texture, unscaled, with its top left corner at `pos`.
+#[derive(Debug, Clone, Copy)]
+pub struct Blit {
+    /// part of the texture to draw, in texels, the whole texture if None
+    pub src: Option<Bounds>,
+    /// where `origin` ends up, goes through the renderer transform and camera
+    pub pos: VX2,
+    /// point of the sprite that sits on `pos` and that it rotates around, 0.0..1.0 of its size
+    pub origin: VX2,
+    pub scale: VX2,
+    /// degrees
+    pub rotation: f32,
+    pub flip_h: bool,
+    pub flip_v: bool,
+    pub sampling: Sampling,
+    /// multiplies the alpha of every texel
+    pub alpha: f32,
+}
+
+impl Blit {
+    pub fn new(pos: VX2) -> Self {
+        Self {
+            src: None,
+            pos,
+            origin: VX2::default(),
+            scale: vx2!(1.0, 1.0),
+            rotation: 0.0,
+            flip_h: false,
+            flip_v: false,
+            sampling: Sampling::Nearest,
+            alpha: 1.0,
+        }
+    }
+    pub fn with_src(mut self, src: Bounds) -> Self {
+        self.src = Some(src);
+        self
+    }
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = vx2!(x, y);
+        self
+    }
+    pub fn with_scale(mut self, sx: f32, sy: f32) -> Self {
+        self.scale = vx2!(sx, sy);
+        self
+    }
+    pub fn with_rotation(mut self, deg: f32) -> Self {
+        self.rotation = deg;
+        self
+    }
+    pub fn with_flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.flip_h = horizontal;
+        self.flip_v = vertical;
+        self
+    }
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+}
+
+/// Texel at `u`, `v` (texels, relative to the top left of `src`), `src` has to be inside `buffer`.
+pub(crate) fn sample(
+    buffer: &Buffer<u32>,
+    src: &Bounds,
+    u: f32,
+    v: f32,
+    sampling: Sampling,
+) -> u32 {
+    let pixels = buffer.as_slice();
+    let stride = buffer.width() as usize;
+    let (x0, y0) = (src.min_x as i32, src.min_y as i32);
+    let (x1, y1) = ((src.max_x as i32 - 1).max(x0), (src.max_y as i32 - 1).max(y0));
+    let texel =
+        |x: i32, y: i32| pixels[y.clamp(y0, y1) as usize * stride + x.clamp(x0, x1) as usize];
+
+    match sampling {
+        Sampling::Nearest => texel(x0 + u.floor() as i32, y0 + v.floor() as i32),
+        Sampling::Bilinear => {
+            // texel centers sit at .5
+            let (u, v) = (u - 0.5, v - 0.5);
+            let (fu, fv) = (u.floor(), v.floor());
+            let (tx, ty) = (u - fu, v - fv);
+            let (x, y) = (x0 + fu as i32, y0 + fv as i32);
+            let top = lerp_color(texel(x, y), texel(x + 1, y), tx);
+            let bottom = lerp_color(texel(x, y + 1), texel(x + 1, y + 1), tx);
+            lerp_color(top, bottom, ty)
+        }
+    }
+}
+
+/// Per channel, alpha included.
+fn lerp_color(a: u32, b: u32, t: f32) -> u32 {
+    let channel = |shift: u32| {
+        let (ca, cb) = ((a >> shift & 0xFF) as f32, (b >> shift & 0xFF) as f32);
+        ((ca + (cb - ca) * t).round() as u32) << shift
+    };
+    channel(0) | channel(8) | channel(16) | channel(24)
+}
@@ -0,0 +1,141 @@
+use crate::error::ResultG;
+
+use super::{Bytes, Image, argb, parse_err};
+
+const FORMAT: &str = "BMP";
+const FILE_HEADER: usize = 14;
+
+/// One channel of a bit field pixel, as mask and shift.
+#[derive(Clone, Copy)]
+struct Field {
+    mask: u32,
+    shift: u32,
+    bits: u32,
+}
+
+impl Field {
+    fn new(mask: u32) -> Self {
+        Self {
+            mask,
+            shift: if mask == 0 { 0 } else { mask.trailing_zeros() },
+            bits: mask.count_ones(),
+        }
+    }
+    /// Channel value scaled to 8 bits, `default` if the mask is empty.
+    fn get(&self, v: u32, default: u8) -> u8 {
+        if self.bits == 0 {
+            return default;
+        }
+        let max = (1u64 << self.bits) - 1;
+        (((v & self.mask) >> self.shift) as u64 * 255 / max) as u8
+    }
+}
+
+/// Uncompressed and bit field BMPs with 1, 4, 8, 16, 24 or 32 bits per pixel.
+/// Run length encoded ones are not supported.
+pub fn decode(bytes: &[u8]) -> ResultG<Image> {
+    let data = Bytes::new(bytes, FORMAT);
+    if data.slice(0, 2)? != b"BM" {
+        return Err(parse_err(FORMAT, "missing signature"));
+    }
+    let pixel_offset = data.u32_le(10)? as usize;
+    let info = data.u32_le(FILE_HEADER)? as usize;
+    let core = info == 12;
+    let (width, height, bpp, compression, colors_used) = if core {
+        let w = data.u16_le(FILE_HEADER + 4)? as i64;
+        let h = data.u16_le(FILE_HEADER + 6)? as i64;
+        (w, h, data.u16_le(FILE_HEADER + 10)?, 0, 0)
+    } else if info >= 40 {
+        (
+            data.u32_le(FILE_HEADER + 4)? as i32 as i64,
+            data.u32_le(FILE_HEADER + 8)? as i32 as i64,
+            data.u16_le(FILE_HEADER + 14)?,
+            data.u32_le(FILE_HEADER + 16)?,
+            data.u32_le(FILE_HEADER + 32)? as usize,
+        )
+    } else {
+        return Err(parse_err(FORMAT, &format!("unknown header size {info}")));
+    };
+    let top_down = height < 0;
+    let (width, height) = (
+        width.unsigned_abs() as usize,
+        height.unsigned_abs() as usize,
+    );
+    Image::pixel_count(width, height, FORMAT)?;
+    let stride = (width * bpp as usize).div_ceil(32) * 4;
+    data.slice(pixel_offset, stride * height)?;
+    let mut image = Image::new(width, height, FORMAT)?;
+
+    // 3 is BI_BITFIELDS, 6 BI_ALPHABITFIELDS; the masks follow a 40 byte header
+    let masks_at = FILE_HEADER + 40;
+    let fields = match (compression, bpp) {
+        (0, 16) => Some([0x7C00, 0x03E0, 0x001F, 0].map(Field::new)),
+        (0, 32) => Some([0xFF0000, 0x00FF00, 0x0000FF, 0].map(Field::new)),
+        (3 | 6, 16 | 32) => {
+            let alpha = if compression == 6 || info >= 56 {
+                data.u32_le(masks_at + 12)?
+            } else {
+                0
+            };
+            Some([
+                Field::new(data.u32_le(masks_at)?),
+                Field::new(data.u32_le(masks_at + 4)?),
+                Field::new(data.u32_le(masks_at + 8)?),
+                Field::new(alpha),
+            ])
+        }
+        (0, 1 | 4 | 8 | 24) => None,
+        _ => {
+            let msg = format!("compression {compression} with {bpp} bits per pixel");
+            return Err(parse_err(FORMAT, &msg));
+        }
+    };
+
+    let palette: Vec<u32> = if bpp <= 8 {
+        let entry = if core { 3 } else { 4 };
+        let count = if colors_used == 0 || colors_used > 1 << bpp {
+            1 << bpp
+        } else {
+            colors_used
+        };
+        let table = data.slice(FILE_HEADER + info, count * entry)?;
+        table
+            .chunks_exact(entry)
+            .map(|c| argb(c[2], c[1], c[0], 255))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    for row in 0..height {
+        let src = data.slice(pixel_offset + row * stride, stride)?;
+        let y = if top_down { row } else { height - 1 - row };
+        let dst = &mut image.pixels[y * width..(y + 1) * width];
+        for (x, px) in dst.iter_mut().enumerate() {
+            *px = match bpp {
+                1 | 4 | 8 => {
+                    let bit = x * bpp as usize;
+                    let shift = 8 - bpp as usize - bit % 8;
+                    let index = (src[bit / 8] >> shift) & ((1u16 << bpp) - 1) as u8;
+                    palette.get(index as usize).copied().unwrap_or(0xFF000000)
+                }
+                24 => argb(src[x * 3 + 2], src[x * 3 + 1], src[x * 3], 255),
+                _ => {
+                    let v = if bpp == 16 {
+                        u16::from_le_bytes([src[x * 2], src[x * 2 + 1]]) as u32
+                    } else {
+                        u32::from_le_bytes([
+                            src[x * 4],
+                            src[x * 4 + 1],
+                            src[x * 4 + 2],
+                            src[x * 4 + 3],
+                        ])
+                    };
+                    let [r, g, b, a] = fields.unwrap_or([Field::new(0); 4]);
+                    argb(r.get(v, 0), g.get(v, 0), b.get(v, 0), a.get(v, 255))
+                }
+            };
+        }
+    }
+    Ok(image)
+}
@@ -0,0 +1,299 @@
+//! Image file decoders, everything ends up as x11 ARGB pixels.
+
+use crate::error::{GrapesError, ResultG};
+
+pub mod bmp;
+pub mod png;
+pub mod qoi;
+pub mod tga;
+
+/// Biggest image accepted, in pixels, so broken headers can't ask for gigabytes.
+/// Decoders also check there is enough data for that many pixels before allocating.
+const MAX_PIXELS: usize = 1 << 24;
+
+/// Decoded pixels, row by row from the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// x11 ARGB
+    pub pixels: Vec<u32>,
+}
+
+impl Image {
+    pub(crate) fn new(width: usize, height: usize, format: &str) -> ResultG<Self> {
+        let len = Self::pixel_count(width, height, format)?;
+        Ok(Self {
+            width,
+            height,
+            pixels: vec![0; len],
+        })
+    }
+
+    /// `width * height`, an error if there are none or more than the decoders accept.
+    pub(crate) fn pixel_count(width: usize, height: usize, format: &str) -> ResultG<usize> {
+        if width == 0 || height == 0 {
+            return Err(parse_err(format, "image has no pixels"));
+        }
+        match width.checked_mul(height) {
+            Some(len) if len <= MAX_PIXELS => Ok(len),
+            _ => Err(parse_err(
+                format,
+                &format!("image too big: {width}x{height}"),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Png,
+    Bmp,
+    Tga,
+    Qoi,
+}
+
+impl Format {
+    /// By the magic bytes at the start, TGA has none so it is the fallback.
+    pub fn guess(bytes: &[u8]) -> Self {
+        if bytes.starts_with(png::SIGNATURE) {
+            Format::Png
+        } else if bytes.starts_with(b"BM") {
+            Format::Bmp
+        } else if bytes.starts_with(b"qoif") {
+            Format::Qoi
+        } else {
+            Format::Tga
+        }
+    }
+}
+
+/// Decodes PNG, BMP, QOI or TGA, picked by the file contents.
+pub fn decode(bytes: &[u8]) -> ResultG<Image> {
+    match Format::guess(bytes) {
+        Format::Png => png::decode(bytes),
+        Format::Bmp => bmp::decode(bytes),
+        Format::Qoi => qoi::decode(bytes),
+        Format::Tga => tga::decode(bytes),
+    }
+}
+
+pub(crate) fn argb(r: u8, g: u8, b: u8, a: u8) -> u32 {
+    (a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32
+}
+
+pub(crate) fn parse_err(format: &str, msg: &str) -> GrapesError {
+    GrapesError::ParsingResource(format!("{format}: {msg}"))
+}
+
+/// Bounds checked reads, running out of bytes is a parsing error.
+pub(crate) struct Bytes<'b> {
+    data: &'b [u8],
+    format: &'static str,
+}
+
+impl<'b> Bytes<'b> {
+    pub(crate) fn new(data: &'b [u8], format: &'static str) -> Self {
+        Self { data, format }
+    }
+    pub(crate) fn slice(&self, at: usize, len: usize) -> ResultG<&'b [u8]> {
+        at.checked_add(len)
+            .and_then(|end| self.data.get(at..end))
+            .ok_or_else(|| parse_err(self.format, "unexpected end of data"))
+    }
+    pub(crate) fn u8(&self, at: usize) -> ResultG<u8> {
+        Ok(self.slice(at, 1)?[0])
+    }
+    pub(crate) fn u16_le(&self, at: usize) -> ResultG<u16> {
+        let b = self.slice(at, 2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+    pub(crate) fn u32_le(&self, at: usize) -> ResultG<u32> {
+        let b = self.slice(at, 4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+    pub(crate) fn u32_be(&self, at: usize) -> ResultG<u32> {
+        let b = self.slice(at, 4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    use crate::{
+        error::GrapesError,
+        textures::{
+            Texture,
+            decode::{argb, decode},
+        },
+    };
+
+    fn png(ihdr: &[u8], extra: &[(&[u8; 4], Vec<u8>)], raw: &[u8]) -> Vec<u8> {
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut chunk = |kind: &[u8; 4], data: &[u8]| {
+            out.extend((data.len() as u32).to_be_bytes());
+            out.extend(kind);
+            out.extend(data);
+            // the decoder doesn't check the crc
+            out.extend([0; 4]);
+        };
+        chunk(b"IHDR", ihdr);
+        for (kind, data) in extra {
+            chunk(kind, data);
+        }
+        chunk(b"IDAT", &compress_to_vec_zlib(raw, 6));
+        chunk(b"IEND", &[]);
+        out
+    }
+
+    #[test]
+    fn png_rgba_and_palette() {
+        // 2x2 rgba, second row "up" filtered against the first
+        let ihdr = [0, 0, 0, 2, 0, 0, 0, 2, 8, 6, 0, 0, 0];
+        let raw = [
+            0, 255, 0, 0, 255, 0, 255, 0, 128, //
+            2, 0, 0, 255, 0, 0, 0, 0, 127,
+        ];
+        let img = decode(&png(&ihdr, &[], &raw)).unwrap();
+        assert_eq!((img.width, img.height), (2, 2));
+        assert_eq!(
+            img.pixels,
+            vec![
+                argb(255, 0, 0, 255),
+                argb(0, 255, 0, 128),
+                argb(255, 0, 255, 255),
+                argb(0, 255, 0, 255)
+            ]
+        );
+
+        // 3x1, 2 bit palette, second entry half transparent
+        let ihdr = [0, 0, 0, 3, 0, 0, 0, 1, 2, 3, 0, 0, 0];
+        let plte = vec![10, 20, 30, 40, 50, 60, 70, 80, 90];
+        let trns = vec![255, 128];
+        let raw = [0, 0b00_01_10_00];
+        let img = decode(&png(&ihdr, &[(b"PLTE", plte), (b"tRNS", trns)], &raw)).unwrap();
+        assert_eq!(
+            img.pixels,
+            vec![
+                argb(10, 20, 30, 255),
+                argb(40, 50, 60, 128),
+                argb(70, 80, 90, 255)
+            ]
+        );
+
+        let broken = png(&ihdr, &[], &raw[..1]);
+        assert!(matches!(
+            decode(&broken[..40]),
+            Err(GrapesError::ParsingResource(_))
+        ));
+        // more data than a 3x1 image has gets cut off while inflating, not after
+        let oversized = png(&ihdr, &[(b"PLTE", vec![0; 3])], &vec![0; 1 << 20]);
+        assert!(matches!(
+            decode(&oversized),
+            Err(GrapesError::ParsingResource(_))
+        ));
+    }
+
+    #[test]
+    fn bmp_tga_qoi() {
+        // 2x2, 24 bit, bottom up, rows padded to 8 bytes
+        let mut bmp = b"BM".to_vec();
+        bmp.extend(70u32.to_le_bytes());
+        bmp.extend([0; 4]);
+        bmp.extend(54u32.to_le_bytes());
+        bmp.extend(40u32.to_le_bytes());
+        bmp.extend(2i32.to_le_bytes());
+        bmp.extend(2i32.to_le_bytes());
+        bmp.extend(1u16.to_le_bytes());
+        bmp.extend(24u16.to_le_bytes());
+        bmp.extend([0; 24]);
+        bmp.extend([255, 0, 0, 0, 255, 0, 0, 0]);
+        bmp.extend([0, 0, 255, 255, 255, 255, 0, 0]);
+        let img = decode(&bmp).unwrap();
+        assert_eq!(
+            img.pixels,
+            vec![
+                argb(255, 0, 0, 255),
+                argb(255, 255, 255, 255),
+                argb(0, 0, 255, 255),
+                argb(0, 255, 0, 255)
+            ]
+        );
+
+        // 3x1 run length encoded true color with alpha, top down
+        let mut tga = vec![0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 1, 0, 32, 0x28];
+        tga.extend([0x81, 0, 0, 255, 255]);
+        tga.extend([0x00, 255, 0, 0, 64]);
+        let img = decode(&tga).unwrap();
+        assert_eq!(
+            img.pixels,
+            vec![
+                argb(255, 0, 0, 255),
+                argb(255, 0, 0, 255),
+                argb(0, 0, 255, 64)
+            ]
+        );
+
+        // 4x1: rgb, run of one, diff, index back to the first
+        let mut qoi = b"qoif".to_vec();
+        qoi.extend(4u32.to_be_bytes());
+        qoi.extend(1u32.to_be_bytes());
+        qoi.extend([4, 0]);
+        qoi.extend([0xFE, 100, 150, 200]);
+        qoi.extend([0xC0]);
+        qoi.extend([0x40 | 3 << 4 | 2 << 2 | 1]);
+        let index = (100 * 3 + 150 * 5 + 200 * 7 + 255 * 11) % 64;
+        qoi.push(index as u8);
+        qoi.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        let img = decode(&qoi).unwrap();
+        assert_eq!(
+            img.pixels,
+            vec![
+                argb(100, 150, 200, 255),
+                argb(100, 150, 200, 255),
+                argb(101, 150, 199, 255),
+                argb(100, 150, 200, 255)
+            ]
+        );
+
+        assert!(matches!(
+            decode(&[1, 2, 3]),
+            Err(GrapesError::ParsingResource(_))
+        ));
+        let missing = Texture::load("does/not/exist.png");
+        assert!(matches!(missing, Err(GrapesError::LoadingResource(_))));
+        let texture = Texture::from_bytes(&qoi).unwrap();
+        assert_eq!(texture.get_buffer().as_slice()[2], argb(101, 150, 199, 255));
+    }
+
+    #[test]
+    fn header_bigger_than_data() {
+        let mut qoi = b"qoif".to_vec();
+        qoi.extend(4096u32.to_be_bytes());
+        qoi.extend(4096u32.to_be_bytes());
+        qoi.extend([4, 0, 0xC0 | 61]);
+        let mut tga = vec![0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 16, 32, 0x28];
+        tga.extend([0xFF, 0, 0, 255, 255]);
+        let mut bmp = b"BM".to_vec();
+        bmp.extend([0; 8]);
+        bmp.extend(54u32.to_le_bytes());
+        bmp.extend(40u32.to_le_bytes());
+        bmp.extend(4096i32.to_le_bytes());
+        bmp.extend((-4096i32).to_le_bytes());
+        bmp.extend(1u16.to_le_bytes());
+        bmp.extend(24u16.to_le_bytes());
+        bmp.extend([0; 24]);
+        bmp.extend([0; 3 * 4096]);
+        // too big for the pixel cap at all
+        let mut huge = qoi.clone();
+        huge[4..8].copy_from_slice(&65536u32.to_be_bytes());
+        for bytes in [qoi, tga, bmp, huge] {
+            assert!(matches!(
+                decode(&bytes),
+                Err(GrapesError::ParsingResource(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use crate::error::ResultG;
+
+use super::{Bytes, Image, argb, parse_err};
+
+pub(crate) const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const FORMAT: &str = "PNG";
+
+/// Adam7 passes as (x start, y start, x step, y step).
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+struct Header {
+    width: usize,
+    height: usize,
+    depth: u8,
+    color: u8,
+    interlaced: bool,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.depth as usize
+    }
+    /// Bytes of one filtered row `width` pixels wide, without the filter byte.
+    fn row_len(&self, width: usize) -> usize {
+        (width * self.bits_per_pixel()).div_ceil(8)
+    }
+    /// (x start, y start, x step, y step, width, height) of every pass with pixels in it.
+    fn passes(&self) -> Vec<(usize, usize, usize, usize, usize, usize)> {
+        let passes = if self.interlaced {
+            &ADAM7[..]
+        } else {
+            &[(0, 0, 1, 1)]
+        };
+        passes
+            .iter()
+            .map(|&(x0, y0, dx, dy)| {
+                let w = self.width.saturating_sub(x0).div_ceil(dx);
+                let h = self.height.saturating_sub(y0).div_ceil(dy);
+                (x0, y0, dx, dy, w, h)
+            })
+            .filter(|&(.., w, h)| w > 0 && h > 0)
+            .collect()
+    }
+    /// Size of the inflated image data, a filter byte in front of every row.
+    fn raw_len(&self) -> usize {
+        self.passes()
+            .iter()
+            .map(|&(.., w, h)| h * (1 + self.row_len(w)))
+            .sum()
+    }
+}
+
+/// Transparency from the tRNS chunk.
+enum Transparent {
+    None,
+    /// alpha per palette entry
+    Palette(Vec<u8>),
+    /// the one gray or rgb value (full bit depth) that is transparent
+    Key([u16; 3]),
+}
+
+/// All color types and bit depths, interlaced or not. Chunk checksums are not checked.
+pub fn decode(bytes: &[u8]) -> ResultG<Image> {
+    if !bytes.starts_with(SIGNATURE) {
+        return Err(parse_err(FORMAT, "missing signature"));
+    }
+    let data = Bytes::new(bytes, FORMAT);
+    let mut at = SIGNATURE.len();
+    let mut header: Option<Header> = None;
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut transparent = Transparent::None;
+    let mut compressed: Vec<u8> = Vec::new();
+
+    loop {
+        let len = data.u32_be(at)? as usize;
+        let kind = data.slice(at + 4, 4)?;
+        let chunk = Bytes::new(data.slice(at + 8, len)?, FORMAT);
+        at += 12 + len;
+        match kind {
+            b"IHDR" => {
+                let h = Header {
+                    width: chunk.u32_be(0)? as usize,
+                    height: chunk.u32_be(4)? as usize,
+                    depth: chunk.u8(8)?,
+                    color: chunk.u8(9)?,
+                    interlaced: chunk.u8(12)? == 1,
+                };
+                let valid_depth = match h.color {
+                    0 => matches!(h.depth, 1 | 2 | 4 | 8 | 16),
+                    3 => matches!(h.depth, 1 | 2 | 4 | 8),
+                    2 | 4 | 6 => matches!(h.depth, 8 | 16),
+                    _ => false,
+                };
+                if !valid_depth {
+                    let msg = format!("color type {} with bit depth {}", h.color, h.depth);
+                    return Err(parse_err(FORMAT, &msg));
+                }
+                header = Some(h);
+            }
+            b"PLTE" => {
+                palette = chunk
+                    .slice(0, len)?
+                    .chunks_exact(3)
+                    .map(|c| [c[0], c[1], c[2]])
+                    .collect();
+            }
+            b"tRNS" => {
+                transparent = match header.as_ref().map(|h| h.color) {
+                    Some(3) => Transparent::Palette(chunk.slice(0, len)?.to_vec()),
+                    Some(0) => {
+                        let gray = chunk.slice(0, 2)?;
+                        let gray = u16::from_be_bytes([gray[0], gray[1]]);
+                        Transparent::Key([gray; 3])
+                    }
+                    Some(2) => {
+                        let rgb = chunk.slice(0, 6)?;
+                        let c = |i: usize| u16::from_be_bytes([rgb[i], rgb[i + 1]]);
+                        Transparent::Key([c(0), c(2), c(4)])
+                    }
+                    _ => Transparent::None,
+                };
+            }
+            b"IDAT" => compressed.extend_from_slice(chunk.slice(0, len)?),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| parse_err(FORMAT, "missing IHDR"))?;
+    if header.color == 3 && palette.is_empty() {
+        return Err(parse_err(FORMAT, "missing PLTE"));
+    }
+    let mut image = Image::new(header.width, header.height, FORMAT)?;
+    // never more than the header says, a tiny file can inflate to gigabytes otherwise
+    let raw = decompress_to_vec_zlib_with_limit(&compressed, header.raw_len())
+        .map_err(|e| parse_err(FORMAT, &format!("inflate failed: {:?}", e.status)))?;
+
+    let bpp = header.bits_per_pixel().div_ceil(8);
+    let mut offset = 0;
+    for (x0, y0, dx, dy, w, h) in header.passes() {
+        let row_len = header.row_len(w);
+        let mut prev = vec![0u8; row_len];
+        let mut row = vec![0u8; row_len];
+        for y in 0..h {
+            let filter = *raw
+                .get(offset)
+                .ok_or_else(|| parse_err(FORMAT, "not enough pixel data"))?;
+            let src = raw
+                .get(offset + 1..offset + 1 + row_len)
+                .ok_or_else(|| parse_err(FORMAT, "not enough pixel data"))?;
+            offset += 1 + row_len;
+            unfilter(filter, src, &prev, &mut row, bpp)?;
+            for x in 0..w {
+                let px = pixel(&header, &row, x, &palette, &transparent);
+                image.pixels[(y0 + y * dy) * header.width + x0 + x * dx] = px;
+            }
+            std::mem::swap(&mut prev, &mut row);
+        }
+    }
+    Ok(image)
+}
+
+fn unfilter(filter: u8, src: &[u8], prev: &[u8], out: &mut [u8], bpp: usize) -> ResultG<()> {
+    for i in 0..src.len() {
+        let a = if i >= bpp { out[i - bpp] } else { 0 };
+        let b = prev[i];
+        let c = if i >= bpp { prev[i - bpp] } else { 0 };
+        let predicted = match filter {
+            0 => 0,
+            1 => a,
+            2 => b,
+            3 => ((a as u16 + b as u16) / 2) as u8,
+            4 => paeth(a, b, c),
+            _ => return Err(parse_err(FORMAT, &format!("unknown filter {filter}"))),
+        };
+        out[i] = src[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Pixel `x` of an unfiltered row as ARGB.
+fn pixel(
+    header: &Header,
+    row: &[u8],
+    x: usize,
+    palette: &[[u8; 3]],
+    transparent: &Transparent,
+) -> u32 {
+    let depth = header.depth as usize;
+    let channels = header.channels();
+    // full bit depth value of channel `c`
+    let sample = |c: usize| -> u16 {
+        let bit = (x * channels + c) * depth;
+        match depth {
+            16 => u16::from_be_bytes([row[bit / 8], row[bit / 8 + 1]]),
+            8 => row[bit / 8] as u16,
+            _ => ((row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1) as u8) as u16,
+        }
+    };
+    // down to 8 bits
+    let scale = |v: u16| -> u8 {
+        match depth {
+            16 => (v >> 8) as u8,
+            8 => v as u8,
+            _ => (v as u32 * 255 / ((1 << depth) - 1)) as u8,
+        }
+    };
+    let keyed = |values: [u16; 3]| matches!(transparent, Transparent::Key(k) if *k == values);
+
+    match header.color {
+        0 => {
+            let v = sample(0);
+            let g = scale(v);
+            argb(g, g, g, if keyed([v; 3]) { 0 } else { 255 })
+        }
+        2 => {
+            let (r, g, b) = (sample(0), sample(1), sample(2));
+            let a = if keyed([r, g, b]) { 0 } else { 255 };
+            argb(scale(r), scale(g), scale(b), a)
+        }
+        3 => {
+            let i = sample(0) as usize;
+            let [r, g, b] = palette.get(i).copied().unwrap_or_default();
+            let a = match transparent {
+                Transparent::Palette(alpha) => alpha.get(i).copied().unwrap_or(255),
+                _ => 255,
+            };
+            argb(r, g, b, a)
+        }
+        4 => {
+            let g = scale(sample(0));
+            argb(g, g, g, scale(sample(1)))
+        }
+        _ => argb(
+            scale(sample(0)),
+            scale(sample(1)),
+            scale(sample(2)),
+            scale(sample(3)),
+        ),
+    }
+}
@@ -0,0 +1,75 @@
+use crate::error::ResultG;
+
+use super::{Bytes, Image, argb, parse_err};
+
+const FORMAT: &str = "QOI";
+const HEADER: usize = 14;
+
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+
+/// The "Quite OK Image" format, 3 or 4 channels.
+pub fn decode(bytes: &[u8]) -> ResultG<Image> {
+    let data = Bytes::new(bytes, FORMAT);
+    if data.slice(0, 4)? != b"qoif" {
+        return Err(parse_err(FORMAT, "missing signature"));
+    }
+    let width = data.u32_be(4)? as usize;
+    let height = data.u32_be(8)? as usize;
+    let channels = data.u8(12)?;
+    if channels != 3 && channels != 4 {
+        return Err(parse_err(FORMAT, &format!("{channels} channels")));
+    }
+    // a run op covers 62 pixels at most
+    let len = Image::pixel_count(width, height, FORMAT)?;
+    data.slice(HEADER, len.div_ceil(62))?;
+    let mut image = Image::new(width, height, FORMAT)?;
+
+    let mut seen = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut run = 0;
+    let mut at = HEADER;
+    for out in image.pixels.iter_mut() {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = data.u8(at)?;
+            at += 1;
+            match op {
+                OP_RGB => {
+                    px[..3].copy_from_slice(data.slice(at, 3)?);
+                    at += 3;
+                }
+                OP_RGBA => {
+                    px.copy_from_slice(data.slice(at, 4)?);
+                    at += 4;
+                }
+                _ => match op & 0xC0 {
+                    OP_INDEX => px = seen[op as usize],
+                    OP_DIFF => {
+                        px[0] = px[0].wrapping_add((op >> 4 & 0x03).wrapping_sub(2));
+                        px[1] = px[1].wrapping_add((op >> 2 & 0x03).wrapping_sub(2));
+                        px[2] = px[2].wrapping_add((op & 0x03).wrapping_sub(2));
+                    }
+                    OP_LUMA => {
+                        let next = data.u8(at)?;
+                        at += 1;
+                        let dg = (op & 0x3F).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(dg.wrapping_sub(8).wrapping_add(next >> 4));
+                        px[1] = px[1].wrapping_add(dg);
+                        px[2] = px[2].wrapping_add(dg.wrapping_sub(8).wrapping_add(next & 0x0F));
+                    }
+                    // 0xC0, repeat the previous pixel
+                    _ => run = (op & 0x3F) as usize,
+                },
+            }
+            let [r, g, b, a] = px.map(|c| c as usize);
+            seen[(r * 3 + g * 5 + b * 7 + a * 11) % 64] = px;
+        }
+        *out = argb(px[0], px[1], px[2], px[3]);
+    }
+    Ok(image)
+}
@@ -0,0 +1,143 @@
+use crate::error::ResultG;
+
+use super::{Bytes, Image, argb, parse_err};
+
+const FORMAT: &str = "TGA";
+const HEADER: usize = 18;
+
+/// Color mapped, true color and gray TGAs, run length encoded or not.
+pub fn decode(bytes: &[u8]) -> ResultG<Image> {
+    let data = Bytes::new(bytes, FORMAT);
+    let id_len = data.u8(0)? as usize;
+    let has_map = data.u8(1)? == 1;
+    let kind = data.u8(2)?;
+    let (map_first, map_len, map_depth) = (
+        data.u16_le(3)? as usize,
+        data.u16_le(5)? as usize,
+        data.u8(7)?,
+    );
+    let width = data.u16_le(12)? as usize;
+    let height = data.u16_le(14)? as usize;
+    let depth = data.u8(16)?;
+    let descriptor = data.u8(17)?;
+    let alpha_bits = descriptor & 0x0F;
+    let right_to_left = descriptor & 0x10 != 0;
+    let top_down = descriptor & 0x20 != 0;
+
+    let (mapped, gray, rle) = match kind {
+        1 => (true, false, false),
+        2 => (false, false, false),
+        3 => (false, true, false),
+        9 => (true, false, true),
+        10 => (false, false, true),
+        11 => (false, true, true),
+        _ => return Err(parse_err(FORMAT, &format!("unknown image type {kind}"))),
+    };
+    let valid_depth = match (mapped, gray) {
+        (true, _) => matches!(depth, 8 | 16) && has_map,
+        (_, true) => depth == 8 || depth == 16,
+        _ => matches!(depth, 15 | 16 | 24 | 32),
+    };
+    if !valid_depth {
+        return Err(parse_err(
+            FORMAT,
+            &format!("image type {kind} with depth {depth}"),
+        ));
+    }
+    let count = Image::pixel_count(width, height, FORMAT)?;
+
+    let mut at = HEADER + id_len;
+    let mut color_map: Vec<u32> = Vec::new();
+    if has_map {
+        let entry = (map_depth as usize).div_ceil(8);
+        let table = data.slice(at, map_len * entry)?;
+        color_map = table
+            .chunks_exact(entry.max(1))
+            .map(|c| true_color(c, map_depth, alpha_bits))
+            .collect();
+        at += map_len * entry;
+    }
+
+    let px_len = (depth as usize).div_ceil(8);
+    // a run length packet has 128 pixels at most
+    let min_len = if rle {
+        count.div_ceil(128) * (1 + px_len)
+    } else {
+        count * px_len
+    };
+    data.slice(at, min_len)?;
+    let mut image = Image::new(width, height, FORMAT)?;
+    let to_argb = |px: &[u8]| -> u32 {
+        if mapped {
+            let index = if px_len == 2 {
+                u16::from_le_bytes([px[0], px[1]]) as usize
+            } else {
+                px[0] as usize
+            };
+            color_map
+                .get(index.wrapping_sub(map_first))
+                .copied()
+                .unwrap_or(0xFF000000)
+        } else if gray {
+            // 16 bit gray is gray and alpha
+            let a = if px_len == 2 { px[1] } else { 255 };
+            argb(px[0], px[0], px[0], a)
+        } else {
+            true_color(px, depth, alpha_bits)
+        }
+    };
+
+    let mut decoded: Vec<u32> = Vec::with_capacity(count);
+    if rle {
+        while decoded.len() < count {
+            let packet = data.u8(at)?;
+            at += 1;
+            let n = (packet & 0x7F) as usize + 1;
+            if packet & 0x80 != 0 {
+                let px = to_argb(data.slice(at, px_len)?);
+                at += px_len;
+                decoded.extend(std::iter::repeat_n(px, n));
+            } else {
+                let raw = data.slice(at, n * px_len)?;
+                at += n * px_len;
+                decoded.extend(raw.chunks_exact(px_len).map(to_argb));
+            }
+        }
+        decoded.truncate(count);
+    } else {
+        let raw = data.slice(at, count * px_len)?;
+        decoded.extend(raw.chunks_exact(px_len).map(to_argb));
+    }
+
+    for (i, px) in decoded.into_iter().enumerate() {
+        let (x, y) = (i % width, i / width);
+        let x = if right_to_left { width - 1 - x } else { x };
+        let y = if top_down { y } else { height - 1 - y };
+        image.pixels[y * width + x] = px;
+    }
+    Ok(image)
+}
+
+/// BGR(A) or 15/16 bit ARRRRRGGGGGBBBBB. Alpha only counts if the header says there is some.
+fn true_color(px: &[u8], depth: u8, alpha_bits: u8) -> u32 {
+    match depth {
+        15 | 16 => {
+            let v = u16::from_le_bytes([px[0], px[1]]);
+            let c = |shift: u16| (((v >> shift) & 0x1F) as u32 * 255 / 31) as u8;
+            let a = if depth == 16 && alpha_bits > 0 && v & 0x8000 == 0 {
+                0
+            } else {
+                255
+            };
+            argb(c(10), c(5), c(0), a)
+        }
+        24 => argb(px[2], px[1], px[0], 255),
+        32 => argb(
+            px[2],
+            px[1],
+            px[0],
+            if alpha_bits > 0 { px[3] } else { 255 },
+        ),
+        _ => 0xFF000000,
+    }
+}
@@ -1,5 +1,18 @@
+pub mod animation;
+pub mod aseprite;
+pub mod atlas;
+pub mod blit;
+pub mod decode;
 
-use crate::{buffer::Buffer, colors::color::Color, linal::vertx2::VX2, vx2};
+use std::path::Path;
+
+use crate::{
+    buffer::Buffer,
+    colors::color::Color,
+    error::{GrapesError, ResultG},
+    linal::vertx2::VX2,
+    vx2,
+};
 
 #[derive(Clone)]
 pub struct Texture {
@@ -20,6 +33,31 @@ impl Texture {
         Self { buffer, size }
     }
 
+    /// Copies x11 ARGB pixels, row by row. None if `pixels` doesn't match the size.
+    pub fn from_pixels(width: usize, height: usize, pixels: &[u32]) -> Option<Self> {
+        if width == 0 || height == 0 || pixels.len() != width * height {
+            return None;
+        }
+        let mut texture = Self::init(vx2!(width as f32, height as f32));
+        texture.buffer.as_mut_slice().copy_from_slice(pixels);
+        Some(texture)
+    }
+
+    /// PNG, BMP, TGA or QOI file contents, the format is picked by the data.
+    pub fn from_bytes(bytes: &[u8]) -> ResultG<Self> {
+        let image = decode::decode(bytes)?;
+        Self::from_pixels(image.width, image.height, &image.pixels)
+            .ok_or_else(|| GrapesError::ParsingResource("image size mismatch".to_string()))
+    }
+
+    /// Reads and decodes a PNG, BMP, TGA or QOI file.
+    pub fn load<P: AsRef<Path>>(path: P) -> ResultG<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| GrapesError::LoadingResource(format!("{}: {e}", path.display())))?;
+        Self::from_bytes(&bytes)
+    }
+
     pub fn size<'t>(&'t self) -> &'t VX2 {
         &self.size
     }